@@ -3,6 +3,7 @@ use super::xml::{BcPayloads, BcXml};
 use super::xml_crypto;
 use crate::RX_TIMEOUT;
 use err_derive::Error;
+use log::*;
 use nom::IResult;
 use nom::{bytes::streaming::take, combinator::*, number::streaming::*, sequence::*};
 use std::io::Read;
@@ -106,9 +107,12 @@ fn bc_body<'a, 'b, 'c>(
         let (buf, body) = bc_modern_msg(context, header, buf)?;
         Ok((buf, BcBody::ModernMsg(body)))
     } else {
-        let (buf, body) = match header.msg_id {
-            MSG_ID_LOGIN => bc_legacy_login_msg(buf)?,
-            _ => (buf, LegacyMsg::UnknownMsg),
+        // Take all of the body so that the padding after the known fields is not
+        // read as the start of the next message
+        let (buf, body_buf) = take(header.body_len)(buf)?;
+        let body = match header.msg_id {
+            MSG_ID_LOGIN => bc_legacy_login_msg(body_buf)?.1,
+            _ => LegacyMsg::UnknownMsg,
         };
         Ok((buf, BcBody::LegacyMsg(body)))
     }
@@ -158,23 +162,15 @@ fn bc_modern_msg<'a, 'b>(
     let payload_len = header.body_len - ext_len;
     let (buf, payload_buf) = take(payload_len)(buf)?;
 
-    let decrypted;
-    let processed_ext_buf = match context.get_encrypted() {
-        EncryptionProtocol::Unencrypted => ext_buf,
-        encryption_protocol => {
-            decrypted =
-                xml_crypto::decrypt(header.channel_id as u32, ext_buf, &encryption_protocol);
-            &decrypted
-        }
-    };
-
     // Now we'll take the buffer that Nom gave a ref to and parse it.
     let extension;
     if ext_len > 0 {
-        // Apply the XML parse function, but throw away the reference to decrypted in the Ok and
-        // Err case. This error-error-error thing is the same idiom Nom uses internally.
-        let parsed = Extension::try_parse(processed_ext_buf)
-            .map_err(|_| Err::Error(make_error(buf, ErrorKind::MapRes)))?;
+        // Turn a failed parse into a Nom error. This error-error-error thing is the same idiom
+        // Nom uses internally.
+        let parsed = decrypt_and_parse(context, header.channel_id as u32, ext_buf, |xml| {
+            Extension::try_parse(xml)
+        })
+        .ok_or_else(|| Err::Error(make_error(buf, ErrorKind::MapRes)))?;
         if let Extension {
             binary_data: Some(1),
             ..
@@ -194,14 +190,13 @@ fn bc_modern_msg<'a, 'b>(
     let payload;
     if payload_len > 0 {
         // Extract remainder of message as binary, if it exists
-        let encryption_protocol = context.get_encrypted();
-        let processed_payload_buf =
-            xml_crypto::decrypt(header.channel_id as u32, payload_buf, &encryption_protocol);
         if context.in_bin_mode.contains(&(header.msg_num)) {
             payload = Some(BcPayloads::Binary(payload_buf.to_vec()));
         } else {
-            let xml = BcXml::try_parse(processed_payload_buf.as_slice())
-                .map_err(|_| Err::Error(make_error(buf, ErrorKind::MapRes)))?;
+            let xml = decrypt_and_parse(context, header.channel_id as u32, payload_buf, |xml| {
+                BcXml::try_parse(xml)
+            })
+            .ok_or_else(|| Err::Error(make_error(buf, ErrorKind::MapRes)))?;
             payload = Some(BcPayloads::BcXml(xml));
         }
     } else {
@@ -211,6 +206,51 @@ fn bc_modern_msg<'a, 'b>(
     Ok((buf, ModernMsg { extension, payload }))
 }
 
+// Decrypts the xml with the negotiated encryption and parses it
+//
+// Some firmwares do not encrypt with the scheme they advertise during login. If the
+// negotiated encryption yields garbage the other schemes are tried before giving up.
+// Such firmwares do this for every message so only the first fallback on a context is
+// a warning
+fn decrypt_and_parse<T, F>(context: &mut BcContext, offset: u32, buf: &[u8], parse: F) -> Option<T>
+where
+    F: Fn(&[u8]) -> Result<T, String>,
+{
+    let encryption_protocol = &context.get_encrypted();
+    if let Ok(parsed) = parse(&xml_crypto::decrypt(offset, buf, encryption_protocol)) {
+        return Some(parsed);
+    }
+
+    let fallbacks = match encryption_protocol {
+        EncryptionProtocol::Unencrypted => vec![EncryptionProtocol::BCEncrypt],
+        EncryptionProtocol::Aes(Some(_)) => vec![
+            EncryptionProtocol::BCEncrypt,
+            EncryptionProtocol::Unencrypted,
+        ],
+        // Aes without a key is decrypted as BCEncrypt
+        EncryptionProtocol::BCEncrypt | EncryptionProtocol::Aes(None) => {
+            vec![EncryptionProtocol::Unencrypted]
+        }
+    };
+    let (parsed, fallback) = fallbacks.iter().find_map(|fallback| {
+        let parsed = parse(&xml_crypto::decrypt(offset, buf, fallback)).ok()?;
+        Some((parsed, fallback))
+    })?;
+    if context.warned_encryption_fallback {
+        debug!(
+            "Xml was not encrypted with the negotiated encryption, it was {:?}",
+            fallback
+        );
+    } else {
+        warn!(
+            "Xml was not encrypted with the negotiated encryption, it was {:?}. Further occurrences are logged at debug level",
+            fallback
+        );
+        context.warned_encryption_fallback = true;
+    }
+    Some(parsed)
+}
+
 fn bc_header(buf: &[u8]) -> IResult<&[u8], BcHeader> {
     let (buf, _magic) = verify(le_u32, |x| *x == MAGIC_HEADER)(buf)?;
     let (buf, msg_id) = le_u32(buf)?;
@@ -241,7 +281,118 @@ fn bc_header(buf: &[u8]) -> IResult<&[u8], BcHeader> {
 mod tests {
     use super::*;
     use crate::bc::xml::*;
+    use crate::bc_protocol::FileSubscriber;
     use assert_matches::assert_matches;
+    use std::io::BufRead;
+    use std::path::PathBuf;
+
+    fn sample(name: &str) -> PathBuf {
+        let dir = std::env::current_dir().unwrap(); // This is crate root during cargo test
+        dir.join("src").join("bc").join("samples").join(name)
+    }
+
+    // Replays a capture through a single context, in the same way that the connection
+    // would read it from the camera, and returns every message that was read
+    //
+    // The files are read as one continuous stream so a capture can be split across
+    // several files. To add a capture from a bug report, save the raw bytes of the
+    // TCP stream (e.g. wireshark's follow TCP stream, show data as raw, save) into
+    // `samples` and replay it in a test
+    fn replay(files: Vec<PathBuf>, encryption_protocol: EncryptionProtocol) -> Vec<Bc> {
+        let encryption_protocol = std::sync::Arc::new(std::sync::Mutex::new(encryption_protocol));
+        let mut context = BcContext::new(encryption_protocol);
+        let mut subscriber = FileSubscriber::from_files(files);
+
+        let mut msgs = vec![];
+        // Stop at the end of the capture rather than waiting for more data
+        while !subscriber.fill_buf().unwrap().is_empty() {
+            match Bc::deserialize(&mut context, &mut subscriber) {
+                Ok(msg) => msgs.push(msg),
+                Err(e) => panic!(
+                    "Message {} of the replay failed to parse: {:?}",
+                    msgs.len(),
+                    e
+                ),
+            }
+        }
+        msgs
+    }
+
+    #[test]
+    // The login messages replayed in the order that they are sent
+    fn test_replay_login() {
+        let msgs = replay(
+            vec![
+                sample("model_sample_legacy_login.bin"),
+                sample("model_sample_modern_login.bin"),
+                sample("modern_login_success.bin"),
+            ],
+            EncryptionProtocol::BCEncrypt,
+        );
+
+        assert_eq!(msgs.len(), 3);
+        assert_matches!(msgs[0].body, BcBody::LegacyMsg(LegacyMsg::LoginMsg { .. }));
+        assert_matches!(
+            msgs[1].body,
+            BcBody::ModernMsg(ModernMsg {
+                payload: Some(BcPayloads::BcXml(BcXml {
+                    encryption: Some(_),
+                    ..
+                })),
+                ..
+            })
+        );
+        assert_eq!(msgs[2].meta.response_code, 200);
+    }
+
+    #[test]
+    // The camera's reply to the legacy login from a camera that negotiates Aes
+    //
+    // Taken from the camera side of the TCP stream in `EncryptionProtocol02_login.pcapng`
+    fn test_replay_aes_login_reply() {
+        let encryption_protocol =
+            std::sync::Arc::new(std::sync::Mutex::new(EncryptionProtocol::BCEncrypt));
+        let mut context = BcContext::new(encryption_protocol);
+        let mut subscriber = FileSubscriber::from_files(vec![sample("modern_login_aes_reply.bin")]);
+        let msg = Bc::deserialize(&mut context, &mut subscriber).unwrap();
+
+        assert_eq!(msg.meta.response_code, 0xdd02);
+        assert_matches!(context.get_encrypted(), EncryptionProtocol::Aes(None));
+        match msg.body {
+            BcBody::ModernMsg(ModernMsg {
+                payload:
+                    Some(BcPayloads::BcXml(BcXml {
+                        encryption: Some(encryption),
+                        ..
+                    })),
+                ..
+            }) => assert_eq!(encryption.nonce, "AB5B32EC757765C2"),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    // Unlike `test_bc_binary_mode` the context must notice the switch to binary mode itself
+    fn test_replay_video_start() {
+        let msgs = replay(
+            vec![
+                sample("modern_video_start1.bin"),
+                sample("modern_video_start2.bin"),
+            ],
+            EncryptionProtocol::BCEncrypt,
+        );
+
+        assert_eq!(msgs.len(), 2);
+        for msg in msgs.iter() {
+            assert_matches!(
+                msg.body,
+                BcBody::ModernMsg(ModernMsg {
+                    payload: Some(BcPayloads::Binary(_)),
+                    ..
+                })
+            );
+        }
+    }
 
     #[test]
     fn test_bc_modern_login() {
@@ -273,6 +424,45 @@ mod tests {
         }
     }
 
+    #[test]
+    // Xml that was not encrypted with the negotiated protocol should still be read
+    fn test_encryption_fallback() {
+        let msg = Bc::new_from_xml(
+            BcMeta {
+                msg_id: MSG_ID_GET_LED_STATUS,
+                channel_id: 0,
+                msg_num: 0,
+                response_code: 200,
+                stream_type: 0,
+                class: 0x0000,
+            },
+            BcXml {
+                led_state: Some(LedState {
+                    version: xml_ver(),
+                    channel_id: 0,
+                    led_version: None,
+                    state: "auto".to_string(),
+                    light_state: "open".to_string(),
+                }),
+                ..Default::default()
+            },
+        );
+        let ser_buf = msg
+            .serialize(vec![], &EncryptionProtocol::Unencrypted)
+            .unwrap();
+
+        let encryption_protocol =
+            std::sync::Arc::new(std::sync::Mutex::new(EncryptionProtocol::BCEncrypt));
+        let mut context = BcContext::new(encryption_protocol);
+        assert!(!context.warned_encryption_fallback);
+        let msg2 = Bc::deserialize::<&[u8]>(&mut context, ser_buf.as_ref()).unwrap();
+        assert_eq!(msg, msg2);
+        // Only the first fallback is a warning
+        assert!(context.warned_encryption_fallback);
+        let msg3 = Bc::deserialize::<&[u8]>(&mut context, ser_buf.as_ref()).unwrap();
+        assert_eq!(msg, msg3);
+    }
+
     #[test]
     // This is an 0xdd03 encryption from an Argus 2
     //
@@ -16,22 +16,42 @@ pub const MSG_ID_TALKABILITY: u32 = 10;
 pub const MSG_ID_TALKRESET: u32 = 11;
 /// Reboot messages have this ID
 pub const MSG_ID_REBOOT: u32 = 23;
+/// Setting the VideoInput (image settings) is done with this ID
+pub const MSG_ID_SET_VIDEO_INPUT: u32 = 25;
+/// Getting the VideoInput (image settings) is done with this ID
+pub const MSG_ID_GET_VIDEO_INPUT: u32 = 26;
 /// Request motion detection messages
 pub const MSG_ID_MOTION_REQUEST: u32 = 31;
 /// Motion detection messages
 pub const MSG_ID_MOTION: u32 = 33;
+/// Getting the OSD (on screen display) settings is done with this ID
+pub const MSG_ID_GET_OSD: u32 = 44;
+/// Setting the OSD (on screen display) settings is done with this ID
+pub const MSG_ID_SET_OSD: u32 = 45;
+/// Getting the Compression (resolution, bitrate and fps) is done with this ID
+pub const MSG_ID_GET_COMPRESSION: u32 = 56;
+/// Setting the Compression (resolution, bitrate and fps) is done with this ID
+pub const MSG_ID_SET_COMPRESSION: u32 = 57;
 /// Version messages have this ID
 pub const MSG_ID_VERSION: u32 = 80;
+/// Getting the Record (recording schedule) is done with this ID
+pub const MSG_ID_GET_RECORD: u32 = 81;
+/// Setting the Record (recording schedule) is done with this ID
+pub const MSG_ID_SET_RECORD: u32 = 82;
 /// Getting PIR status messages have this ID
 pub const MSG_ID_GET_PIR_ALARM: u32 = 212;
 /// Setting PIR status messages have this ID
 pub const MSG_ID_START_PIR_ALARM: u32 = 213;
 /// Ping messages have this ID
 pub const MSG_ID_PING: u32 = 93;
+/// Getting the HddInfoList (storage info) is done with this ID
+pub const MSG_ID_GET_HDD_INFO: u32 = 102;
 /// General system info messages have this ID
 pub const MSG_ID_GET_GENERAL: u32 = 104;
 /// Setting general system info (clock mostly) messages have this ID
 pub const MSG_ID_SET_GENERAL: u32 = 105;
+/// Requesting the Support xml (camera capabilities) is done with this ID
+pub const MSG_ID_SUPPORT: u32 = 199;
 /// Will send the talk config for talk back data to follow this msg
 pub const MSG_ID_TALKCONFIG: u32 = 201;
 /// Used to send talk back binary data
@@ -181,6 +201,9 @@ pub(crate) struct BcContext {
     // Arc<Mutex<EncryptionProtocol>> because it is shared between context
     // and connection for deserialisation and serialistion respectivly
     pub(super) encryption_protocol: Arc<Mutex<EncryptionProtocol>>,
+    // Set once the xml has been decrypted with a different encryption than negotiated
+    // and that has been warned about
+    pub(super) warned_encryption_fallback: bool,
 }
 
 impl Bc {
@@ -221,6 +244,7 @@ impl BcContext {
         BcContext {
             in_bin_mode: HashSet::new(),
             encryption_protocol,
+            warned_encryption_fallback: false,
         }
     }
 
@@ -72,6 +72,30 @@ pub struct BcXml {
     /// Received when motion is detected
     #[yaserde(rename = "AlarmEventList")]
     pub alarm_event_list: Option<AlarmEventList>,
+    /// VideoInput xml is sent or recieved as part of the image settings get/setting
+    #[yaserde(rename = "VideoInput")]
+    pub video_input: Option<VideoInput>,
+    /// InputAdvanceCfg xml is sent or recieved alongside the VideoInput xml
+    #[yaserde(rename = "InputAdvanceCfg")]
+    pub input_advance_cfg: Option<InputAdvanceCfg>,
+    /// Support xml is received in reply to a request for the camera's capabilities
+    #[yaserde(rename = "Support")]
+    pub support: Option<Support>,
+    /// OsdChannelName xml is sent or recieved as part of the OSD get/setting
+    #[yaserde(rename = "OsdChannelName")]
+    pub osd_channel_name: Option<OsdChannelName>,
+    /// OsdDatetime xml is sent or recieved as part of the OSD get/setting
+    #[yaserde(rename = "OsdDatetime")]
+    pub osd_datetime: Option<OsdDatetime>,
+    /// Compression xml is sent or recieved as part of the encoding get/setting
+    #[yaserde(rename = "Compression")]
+    pub compression: Option<Compression>,
+    /// Record xml is sent or recieved as part of the record schedule get/setting
+    #[yaserde(rename = "Record")]
+    pub record: Option<Record>,
+    /// HddInfoList xml is received in reply to a request for the storage info
+    #[yaserde(rename = "HddInfoList")]
+    pub hdd_info_list: Option<HddInfoList>,
 }
 
 impl BcXml {
@@ -285,8 +309,8 @@ pub struct Norm {
     /// XML Version
     #[yaserde(attribute)]
     pub version: String,
-    // This is usually just `"NTSC"`
-    norm: String,
+    /// The video standard either `"NTSC"` or `"PAL"`
+    pub norm: String,
 }
 
 /// LedState xml
@@ -326,7 +350,242 @@ pub struct RfAlarmCfg {
     /// reduce False alarm boolean
     pub reduceFalseAlarm: u8,
     /// XML time block for all week days
-    pub timeBlockList: String,
+    pub timeBlockList: TimeBlockList,
+}
+
+/// timeBlockList xml
+#[derive(PartialEq, Eq, Default, Debug, YaDeserialize, YaSerialize)]
+pub struct TimeBlockList {
+    /// The schedule of the PIR or recording, usually one time block for each day of the week
+    #[yaserde(rename = "timeBlock")]
+    pub time_block: Vec<TimeBlock>,
+}
+
+/// timeBlock xml
+#[derive(PartialEq, Eq, Default, Debug, YaDeserialize, YaSerialize)]
+pub struct TimeBlock {
+    /// Whether the PIR or recording is active during this time block
+    pub enable: u8,
+    /// Day of the week this time block applies to e.g. `"Sunday"`
+    #[yaserde(rename = "weekDay")]
+    pub week_day: String,
+    /// Hour of the day the time block begins `0..=23`
+    #[yaserde(rename = "beginHour")]
+    pub begin_hour: u8,
+    /// Hour of the day the time block ends `0..=23`
+    #[yaserde(rename = "endHour")]
+    pub end_hour: u8,
+}
+
+/// Record xml
+///
+/// The recording schedule of the camera
+#[derive(PartialEq, Eq, Default, Debug, YaDeserialize, YaSerialize)]
+pub struct Record {
+    /// XML Version
+    #[yaserde(attribute)]
+    pub version: String,
+    /// Channel ID of camera to get/set its record schedule
+    #[yaserde(rename = "channelId")]
+    pub channel_id: u8,
+    /// Whether recording is on `0` or `1`
+    pub enable: u8,
+    /// The schedules for each type of recording
+    #[yaserde(rename = "ScheduleList")]
+    pub schedule_list: ScheduleList,
+}
+
+/// ScheduleList xml
+#[derive(PartialEq, Eq, Default, Debug, YaDeserialize, YaSerialize)]
+pub struct ScheduleList {
+    /// One schedule for each type of recording
+    #[yaserde(rename = "Schedule")]
+    pub schedule: Vec<Schedule>,
+}
+
+/// Schedule xml
+#[derive(PartialEq, Eq, Default, Debug, YaDeserialize, YaSerialize)]
+pub struct Schedule {
+    /// What triggers the recording, known values are `"MD"` for motion and `"none"`
+    /// for continuous recording
+    #[yaserde(rename = "alarmType")]
+    pub alarm_type: String,
+    /// The times this type of recording is active
+    #[yaserde(rename = "timeBlockList")]
+    pub time_block_list: TimeBlockList,
+}
+
+/// VideoInput xml
+///
+/// Contains the image settings of the camera. All values are in the range `0..=255`
+/// with `128` being the default
+#[derive(PartialEq, Eq, Default, Debug, YaDeserialize, YaSerialize)]
+pub struct VideoInput {
+    /// XML Version
+    #[yaserde(attribute)]
+    pub version: String,
+    /// Channel ID of camera to get/set its image settings
+    #[yaserde(rename = "channelId")]
+    pub channel_id: u8,
+    /// Brightness of the image
+    pub bright: u8,
+    /// Contrast of the image
+    pub contrast: u8,
+    /// Saturation of the image
+    pub saturation: u8,
+    /// Hue of the image
+    pub hue: u8,
+    /// Sharpness of the image, not sent by all cameras
+    pub sharpen: Option<u8>,
+}
+
+/// InputAdvanceCfg xml
+///
+/// The advanced image settings that accompany the [VideoInput] xml. Only the
+/// power line frequency (anti-flicker) is currently deserialized
+#[derive(PartialEq, Eq, Default, Debug, YaDeserialize, YaSerialize)]
+pub struct InputAdvanceCfg {
+    /// XML Version
+    #[yaserde(attribute)]
+    pub version: String,
+    /// Channel ID of camera to get/set its advanced image settings
+    #[yaserde(rename = "channelId")]
+    pub channel_id: u8,
+    /// Observed value is `1`
+    #[yaserde(rename = "digitalChannel")]
+    pub digital_channel: Option<u8>,
+    /// The anti-flicker settings
+    #[yaserde(rename = "PowerLineFrequency")]
+    pub power_line_frequency: Option<PowerLineFrequency>,
+}
+
+/// PowerLineFrequency xml
+///
+/// Matches the exposure to the mains frequency to stop lights flickering in the image
+#[derive(PartialEq, Eq, Default, Debug, YaDeserialize, YaSerialize)]
+pub struct PowerLineFrequency {
+    /// The mains frequency, known values are `"50hz"` and `"60hz"`
+    pub mode: String,
+    /// Whether anti-flicker is on `0` or `1`
+    pub enable: u8,
+}
+
+/// OsdChannelName xml
+///
+/// The camera name that is drawn over the video
+#[derive(PartialEq, Eq, Default, Debug, YaDeserialize, YaSerialize)]
+pub struct OsdChannelName {
+    /// XML Version
+    #[yaserde(attribute)]
+    pub version: String,
+    /// Channel ID of camera to get/set its OSD
+    #[yaserde(rename = "channelId")]
+    pub channel_id: u8,
+    /// The text to draw
+    pub name: String,
+    /// Whether the name is drawn `0` or `1`
+    pub enable: u8,
+    /// Position of the name, observed values are `65536` and `65537`
+    #[yaserde(rename = "topLeftX")]
+    pub top_left_x: u32,
+    /// Position of the name, observed values are `1` and `65536`
+    #[yaserde(rename = "topLeftY")]
+    pub top_left_y: u32,
+    /// Whether the watermark is drawn `0` or `1`
+    #[yaserde(rename = "enWatermark")]
+    pub en_watermark: Option<u8>,
+    /// Whether the text has a background colour `0` or `1`
+    #[yaserde(rename = "enBgcolor")]
+    pub en_bgcolor: Option<u8>,
+}
+
+/// OsdDatetime xml
+///
+/// The date and time that is drawn over the video
+#[derive(PartialEq, Eq, Default, Debug, YaDeserialize, YaSerialize)]
+pub struct OsdDatetime {
+    /// XML Version
+    #[yaserde(attribute)]
+    pub version: String,
+    /// Channel ID of camera to get/set its OSD
+    #[yaserde(rename = "channelId")]
+    pub channel_id: u8,
+    /// Whether the date and time is drawn `0` or `1`
+    pub enable: u8,
+    /// Position of the date and time, observed values are `65536` and `65537`
+    #[yaserde(rename = "topLeftX")]
+    pub top_left_x: u32,
+    /// Position of the date and time, observed values are `1` and `65536`
+    #[yaserde(rename = "topLeftY")]
+    pub top_left_y: u32,
+    /// Only recieved from the camera. Should be None when setting the OsdDatetime
+    pub width: Option<u32>,
+    /// Only recieved from the camera. Should be None when setting the OsdDatetime
+    pub height: Option<u32>,
+    /// Language of the date, known values are `"Chinese"`
+    pub language: String,
+}
+
+/// HddInfoList xml
+///
+/// The storage of the camera. Only the reply of a camera without a disk has been
+/// captured so the per disk entries are not yet deserialized
+#[derive(PartialEq, Eq, Default, Debug, YaDeserialize, YaSerialize)]
+pub struct HddInfoList {
+    /// XML Version
+    #[yaserde(attribute)]
+    pub version: String,
+}
+
+/// Compression xml
+///
+/// The encoding settings of each of the streams
+#[derive(PartialEq, Eq, Default, Debug, YaDeserialize, YaSerialize)]
+pub struct Compression {
+    /// XML Version
+    #[yaserde(attribute)]
+    pub version: String,
+    /// Channel ID of camera to get/set its encoding
+    #[yaserde(rename = "channelId")]
+    pub channel_id: u8,
+    /// Only recieved from the camera. Should be None when setting the Compression
+    #[yaserde(rename = "isNoTranslateFrame")]
+    pub is_no_translate_frame: Option<u8>,
+    /// Encoding of the main stream
+    #[yaserde(rename = "mainStream")]
+    pub main_stream: StreamCompression,
+    /// Encoding of the sub stream
+    #[yaserde(rename = "subStream")]
+    pub sub_stream: StreamCompression,
+    /// Encoding of the third stream. Only recieved from the camera. Should be None when setting
+    /// the Compression
+    #[yaserde(rename = "thirdStream")]
+    pub third_stream: Option<StreamCompression>,
+}
+
+/// Encoding settings of a single stream
+#[derive(PartialEq, Eq, Default, Debug, YaDeserialize, YaSerialize)]
+pub struct StreamCompression {
+    /// Whether audio is included in the stream `0` or `1`
+    pub audio: u8,
+    /// The resolution as `"{width}*{height}"`
+    #[yaserde(rename = "resolutionName")]
+    pub resolution_name: String,
+    /// Width of the video
+    pub width: u32,
+    /// Height of the video
+    pub height: u32,
+    /// Bitrate control, known values are `"cbr"` and `"vbr"`
+    #[yaserde(rename = "encoderType")]
+    pub encoder_type: String,
+    /// Frames per second
+    pub frame: u32,
+    /// Bitrate in kbps
+    #[yaserde(rename = "bitRate")]
+    pub bit_rate: u32,
+    /// The H264 profile, known values are `"high"` and `"default"`
+    #[yaserde(rename = "encoderProfile")]
+    pub encoder_profile: String,
 }
 
 /// TalkConfig xml
@@ -444,6 +703,179 @@ pub struct AlarmEvent {
     pub timeStamp: i32,
 }
 
+/// Support xml
+///
+/// Describes the capabilities of the camera. Values are usually `0` for unsupported
+/// and non zero for supported (sometimes the value is a version number). Not every
+/// firmware sends every field.
+///
+/// Also serializes with serde so it can be printed as JSON. The serde trait is not
+/// imported as its `serialize` method would clash with the yaserde one
+#[derive(PartialEq, Eq, Default, Debug, YaDeserialize, YaSerialize, serde::Serialize)]
+pub struct Support {
+    /// XML Version
+    #[yaserde(attribute)]
+    pub version: String,
+    /// Number of IO input ports
+    #[yaserde(rename = "IOInputPortNum")]
+    pub io_input_port_num: Option<u32>,
+    /// Number of IO output ports
+    #[yaserde(rename = "IOOutputPortNum")]
+    pub io_output_port_num: Option<u32>,
+    /// Number of disks
+    #[yaserde(rename = "diskNum")]
+    pub disk_num: Option<u32>,
+    /// Number of channels, this is more than `1` on NVRs
+    #[yaserde(rename = "channelNum")]
+    pub channel_num: Option<u32>,
+    /// Number of audio channels
+    #[yaserde(rename = "audioNum")]
+    pub audio_num: Option<u32>,
+    /// PTZ mode known values are `"none"` and `"pt"`
+    #[yaserde(rename = "ptzMode")]
+    pub ptz_mode: Option<String>,
+    /// PTZ configuration
+    #[yaserde(rename = "ptzCfg")]
+    pub ptz_cfg: Option<u32>,
+    /// RS485 support
+    #[yaserde(rename = "B485")]
+    pub b485: Option<u32>,
+    /// Automatic firmware updates
+    #[yaserde(rename = "autoUpdate")]
+    pub auto_update: Option<u32>,
+    /// Push notifications of alarms
+    #[yaserde(rename = "pushAlarm")]
+    pub push_alarm: Option<u32>,
+    /// FTP uploads
+    pub ftp: Option<u32>,
+    /// FTP test uploads
+    #[yaserde(rename = "ftpTest")]
+    pub ftp_test: Option<u32>,
+    /// Email alerts
+    pub email: Option<u32>,
+    /// Wifi
+    pub wifi: Option<u32>,
+    /// Recording
+    pub record: Option<u32>,
+    /// Wifi testing
+    #[yaserde(rename = "wifiTest")]
+    pub wifi_test: Option<u32>,
+    /// RTSP server on the camera
+    pub rtsp: Option<u32>,
+    /// ONVIF server on the camera
+    pub onvif: Option<u32>,
+    /// Talk back audio
+    #[yaserde(rename = "audioTalk")]
+    pub audio_talk: Option<u32>,
+    /// Version of the RF (PIR) alarm
+    #[yaserde(rename = "rfVersion")]
+    pub rf_version: Option<u32>,
+    /// RTMP server on the camera
+    pub rtmp: Option<u32>,
+    /// Set when the camera has no extern (balanced) stream
+    #[yaserde(rename = "noExternStream")]
+    pub no_extern_stream: Option<u32>,
+    /// Time format
+    #[yaserde(rename = "timeFormat")]
+    pub time_format: Option<u32>,
+    /// Version of the DDNS support
+    #[yaserde(rename = "ddnsVersion")]
+    pub ddns_version: Option<u32>,
+    /// Version of the email support
+    #[yaserde(rename = "emailVersion")]
+    pub email_version: Option<u32>,
+    /// Version of the push notification support
+    #[yaserde(rename = "pushVersion")]
+    pub push_version: Option<u32>,
+    /// Push notification type
+    #[yaserde(rename = "pushType")]
+    pub push_type: Option<u32>,
+    /// Audio alarm (siren)
+    #[yaserde(rename = "audioAlarm")]
+    pub audio_alarm: Option<u32>,
+    /// Access point mode
+    #[yaserde(rename = "apMode")]
+    pub ap_mode: Option<u32>,
+    /// Version of the cloud support
+    #[yaserde(rename = "cloudVersion")]
+    pub cloud_version: Option<u32>,
+    /// Version of the replay support
+    #[yaserde(rename = "replayVersion")]
+    pub replay_version: Option<u32>,
+    /// Version of the mobile communication support
+    #[yaserde(rename = "mobComVersion")]
+    pub mob_com_version: Option<u32>,
+    /// Time synchronisation
+    #[yaserde(rename = "syncTime")]
+    pub sync_time: Option<u32>,
+    /// Network port configuration
+    #[yaserde(rename = "netPort")]
+    pub net_port: Option<u32>,
+    /// Video standard (PAL/NTSC) configuration
+    #[yaserde(rename = "videoStandard")]
+    pub video_standard: Option<u32>,
+    /// The capabilities of each channel
+    #[yaserde(rename = "item")]
+    pub items: Vec<SupportItem>,
+}
+
+/// The capabilities of a single channel in the Support xml
+#[derive(PartialEq, Eq, Default, Debug, YaDeserialize, YaSerialize, serde::Serialize)]
+pub struct SupportItem {
+    /// The channel these capabilities are for
+    #[yaserde(rename = "chnID")]
+    pub chn_id: u8,
+    /// PTZ type
+    #[yaserde(rename = "ptzType")]
+    pub ptz_type: Option<u32>,
+    /// PTZ presets
+    #[yaserde(rename = "ptzPreset")]
+    pub ptz_preset: Option<u32>,
+    /// PTZ patrols
+    #[yaserde(rename = "ptzPatrol")]
+    pub ptz_patrol: Option<u32>,
+    /// PTZ patterns
+    #[yaserde(rename = "ptzTattern")]
+    pub ptz_tattern: Option<u32>,
+    /// PTZ control
+    #[yaserde(rename = "ptzControl")]
+    pub ptz_control: Option<u32>,
+    /// RF (PIR) configuration
+    #[yaserde(rename = "rfCfg")]
+    pub rf_cfg: Option<u32>,
+    /// Set when the channel has no audio
+    #[yaserde(rename = "noAudio")]
+    pub no_audio: Option<u32>,
+    /// Auto focus
+    #[yaserde(rename = "autoFocus")]
+    pub auto_focus: Option<u32>,
+    /// Video clips
+    #[yaserde(rename = "videoClip")]
+    pub video_clip: Option<u32>,
+    /// Battery powered
+    pub battery: Option<u32>,
+    /// ISP (image) configuration
+    #[yaserde(rename = "ispCfg")]
+    pub isp_cfg: Option<u32>,
+    /// OSD configuration
+    #[yaserde(rename = "osdCfg")]
+    pub osd_cfg: Option<u32>,
+    /// Battery analysis
+    #[yaserde(rename = "batAnalysis")]
+    pub bat_analysis: Option<u32>,
+    /// Dynamic resolution
+    #[yaserde(rename = "dynamicReso")]
+    pub dynamic_reso: Option<u32>,
+    /// Version of the audio support
+    #[yaserde(rename = "audioVersion")]
+    pub audio_version: Option<u32>,
+    /// LED control
+    #[yaserde(rename = "ledCtrl")]
+    pub led_ctrl: Option<u32>,
+    /// Motion detection
+    pub motion: Option<u32>,
+}
+
 /// Convience function to return the xml version used throughout the library
 pub fn xml_ver() -> String {
     "1.1".to_string()
@@ -547,6 +979,23 @@ fn test_login_ser() {
     assert_eq!(b2, b3);
 }
 
+#[test]
+fn test_login_net_udp_port_ser() {
+    let b = BcXml {
+        login_net: Some(LoginNet {
+            udp_port: 53678,
+            ..LoginNet::default()
+        }),
+        ..BcXml::default()
+    };
+
+    let serialized = String::from_utf8(b.serialize(vec![]).unwrap()).unwrap();
+    assert!(serialized.contains("<udpPort>53678</udpPort>"));
+
+    let b2 = BcXml::try_parse(serialized.as_bytes()).unwrap();
+    assert_eq!(b2.login_net.unwrap().udp_port, 53678);
+}
+
 #[test]
 fn test_deviceinfo_partial_deser() {
     let sample = indoc!(
@@ -593,6 +1042,472 @@ fn test_deviceinfo_partial_deser() {
     }
 }
 
+#[test]
+fn test_videoinput_deser() {
+    let sample = indoc!(
+        r#"
+        <?xml version="1.0" encoding="UTF-8" ?>
+        <body>
+        <VideoInput version="1.1">
+        <channelId>0</channelId>
+        <bright>128</bright>
+        <contrast>128</contrast>
+        <saturation>128</saturation>
+        <hue>128</hue>
+        <sharpen>166</sharpen>
+        </VideoInput>
+        <InputAdvanceCfg version="1.1">
+        <channelId>0</channelId>
+        <digitalChannel>1</digitalChannel>
+        <PowerLineFrequency>
+        <mode>50hz</mode>
+        <enable>0</enable>
+        </PowerLineFrequency>
+        <Exposure>
+        <mode>auto</mode>
+        <shutterLevel>1/30</shutterLevel>
+        <gainLevel>50</gainLevel>
+        </Exposure>
+        <mirror>0</mirror>
+        <flip>0</flip>
+        </InputAdvanceCfg>
+        </body>"#
+    );
+
+    // Only the PowerLineFrequency of InputAdvanceCfg is deserialized the rest should be skipped
+    let b = BcXml::try_parse(sample.as_bytes()).unwrap();
+    match b {
+        BcXml {
+            video_input:
+                Some(VideoInput {
+                    bright: 128,
+                    sharpen: Some(166),
+                    ..
+                }),
+            input_advance_cfg:
+                Some(InputAdvanceCfg {
+                    digital_channel: Some(1),
+                    power_line_frequency:
+                        Some(PowerLineFrequency {
+                            ref mode,
+                            enable: 0,
+                        }),
+                    ..
+                }),
+            ..
+        } if mode == "50hz" => {}
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_videoinput_no_sharpen_deser() {
+    let sample = indoc!(
+        r#"
+        <?xml version="1.0" encoding="UTF-8" ?>
+        <body>
+        <VideoInput version="1.1">
+        <channelId>0</channelId>
+        <bright>128</bright>
+        <contrast>128</contrast>
+        <saturation>128</saturation>
+        <hue>128</hue>
+        </VideoInput>
+        </body>"#
+    );
+
+    // Sample from msg 78 which does not include sharpen
+    let b = BcXml::try_parse(sample.as_bytes()).unwrap();
+    match b {
+        BcXml {
+            video_input:
+                Some(VideoInput {
+                    hue: 128,
+                    sharpen: None,
+                    ..
+                }),
+            input_advance_cfg: None,
+            ..
+        } => {}
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_rfalarmcfg_deser() {
+    let sample = indoc!(
+        r#"
+        <?xml version="1.0" encoding="UTF-8" ?>
+        <body>
+        <rfAlarmCfg version="1.1">
+        <rfID>0</rfID>
+        <enable>1</enable>
+        <sensitivity>1</sensitivity>
+        <sensiValue>16</sensiValue>
+        <reduceFalseAlarm>0</reduceFalseAlarm>
+        <timeBlockList>
+        <timeBlock>
+        <enable>0</enable>
+        <weekDay>Sunday</weekDay>
+        <beginHour>0</beginHour>
+        <endHour>23</endHour>
+        </timeBlock>
+        <timeBlock>
+        <enable>1</enable>
+        <weekDay>Monday</weekDay>
+        <beginHour>18</beginHour>
+        <endHour>23</endHour>
+        </timeBlock>
+        </timeBlockList>
+        <alarmHandle>
+        <item>
+        <channel>0</channel>
+        <handleType>none</handleType>
+        </item>
+        </alarmHandle>
+        </rfAlarmCfg>
+        </body>"#
+    );
+
+    let b = BcXml::try_parse(sample.as_bytes()).unwrap();
+    match b {
+        BcXml {
+            rf_alarm_cfg:
+                Some(RfAlarmCfg {
+                    sensiValue: 16,
+                    timeBlockList: TimeBlockList { ref time_block },
+                    ..
+                }),
+            ..
+        } => assert_eq!(
+            time_block[1],
+            TimeBlock {
+                enable: 1,
+                week_day: "Monday".to_string(),
+                begin_hour: 18,
+                end_hour: 23,
+            }
+        ),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_record_deser() {
+    let sample = indoc!(
+        r#"
+        <?xml version="1.0" encoding="UTF-8" ?>
+        <body>
+        <Record version="1.1">
+        <channelId>0</channelId>
+        <enable>1</enable>
+        <ScheduleList>
+        <Schedule>
+        <alarmType>MD</alarmType>
+        <timeBlockList>
+        <timeBlock>
+        <enable>1</enable>
+        <weekDay>Monday</weekDay>
+        <beginHour>0</beginHour>
+        <endHour>23</endHour>
+        </timeBlock>
+        <timeBlock>
+        <enable>1</enable>
+        <weekDay>Tuesday</weekDay>
+        <beginHour>0</beginHour>
+        <endHour>12</endHour>
+        </timeBlock>
+        <timeBlock>
+        <enable>1</enable>
+        <weekDay>Tuesday</weekDay>
+        <beginHour>14</beginHour>
+        <endHour>23</endHour>
+        </timeBlock>
+        </timeBlockList>
+        </Schedule>
+        <Schedule>
+        <alarmType>none</alarmType>
+        <timeBlockList>
+        <timeBlock>
+        <enable>1</enable>
+        <weekDay>Tuesday</weekDay>
+        <beginHour>13</beginHour>
+        <endHour>13</endHour>
+        </timeBlock>
+        </timeBlockList>
+        </Schedule>
+        </ScheduleList>
+        </Record>
+        </body>"#
+    );
+
+    let b = BcXml::try_parse(sample.as_bytes()).unwrap();
+    match b {
+        BcXml {
+            record:
+                Some(Record {
+                    enable: 1,
+                    schedule_list: ScheduleList { ref schedule },
+                    ..
+                }),
+            ..
+        } => {
+            assert_eq!(schedule.len(), 2);
+            assert_eq!(schedule[0].alarm_type, "MD");
+            assert_eq!(schedule[0].time_block_list.time_block.len(), 3);
+            assert_eq!(schedule[1].alarm_type, "none");
+            assert_eq!(
+                schedule[1].time_block_list.time_block[0],
+                TimeBlock {
+                    enable: 1,
+                    week_day: "Tuesday".to_string(),
+                    begin_hour: 13,
+                    end_hour: 13,
+                }
+            );
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_hddinfolist_deser() {
+    let sample = indoc!(
+        r#"
+        <?xml version="1.0" encoding="UTF-8" ?>
+        <body>
+        <HddInfoList version="1.1" />
+        </body>"#
+    );
+
+    let b = BcXml::try_parse(sample.as_bytes()).unwrap();
+    match b {
+        BcXml {
+            hdd_info_list: Some(HddInfoList { ref version }),
+            ..
+        } if version == "1.1" => {}
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_support_deser() {
+    let sample = indoc!(
+        r#"
+        <?xml version="1.0" encoding="UTF-8" ?>
+        <body>
+        <Support version="1.1">
+        <IOInputPortNum>0</IOInputPortNum>
+        <IOOutputPortNum>0</IOOutputPortNum>
+        <diskNum>0</diskNum>
+        <channelNum>1</channelNum>
+        <audioNum>1</audioNum>
+        <ptzMode>pt</ptzMode>
+        <ptzCfg>0</ptzCfg>
+        <B485>0</B485>
+        <autoUpdate>0</autoUpdate>
+        <pushAlarm>1</pushAlarm>
+        <ftp>0</ftp>
+        <ftpTest>1</ftpTest>
+        <email>1</email>
+        <wifi>5</wifi>
+        <record>0</record>
+        <wifiTest>1</wifiTest>
+        <rtsp>0</rtsp>
+        <onvif>0</onvif>
+        <audioTalk>1</audioTalk>
+        <rfVersion>0</rfVersion>
+        <rtmp>0</rtmp>
+        <noExternStream>1</noExternStream>
+        <timeFormat>1</timeFormat>
+        <ddnsVersion>1</ddnsVersion>
+        <emailVersion>3</emailVersion>
+        <pushVersion>1</pushVersion>
+        <pushType>1</pushType>
+        <audioAlarm>1</audioAlarm>
+        <apMode>0</apMode>
+        <cloudVersion>30</cloudVersion>
+        <replayVersion>1</replayVersion>
+        <mobComVersion>0</mobComVersion>
+        <syncTime>1</syncTime>
+        <netPort>1</netPort>
+        <videoStandard>0</videoStandard>
+        <smartHome>
+        <version>1</version>
+        <item>
+        <name>googleHome</name>
+        <ver>1</ver>
+        </item>
+        </smartHome>
+        <item>
+        <chnID>0</chnID>
+        <ptzType>3</ptzType>
+        <ptzPreset>0</ptzPreset>
+        <ptzPatrol>0</ptzPatrol>
+        <ptzTattern>0</ptzTattern>
+        <ptzControl>0</ptzControl>
+        <rfCfg>0</rfCfg>
+        <noAudio>0</noAudio>
+        <autoFocus>0</autoFocus>
+        <videoClip>0</videoClip>
+        <battery>0</battery>
+        <ispCfg>0</ispCfg>
+        <osdCfg>1</osdCfg>
+        <batAnalysis>0</batAnalysis>
+        <dynamicReso>0</dynamicReso>
+        <audioVersion>15</audioVersion>
+        <ledCtrl>1</ledCtrl>
+        <motion>1</motion>
+        </item>
+        </Support>
+        </body>"#
+    );
+
+    // smartHome is not deserialized and should be skipped
+    let b = BcXml::try_parse(sample.as_bytes()).unwrap();
+    match b {
+        BcXml {
+            support:
+                Some(Support {
+                    ptz_mode: Some(ref ptz_mode),
+                    audio_talk: Some(1),
+                    ref items,
+                    ..
+                }),
+            ..
+        } if ptz_mode == "pt"
+            && matches!(
+                items.as_slice(),
+                [SupportItem {
+                    chn_id: 0,
+                    ptz_type: Some(3),
+                    led_ctrl: Some(1),
+                    ..
+                }]
+            ) => {}
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_osd_deser() {
+    let sample = indoc!(
+        r#"
+        <?xml version="1.0" encoding="UTF-8" ?>
+        <body>
+        <OsdChannelName version="1.1">
+        <channelId>0</channelId>
+        <name>Cammy02</name>
+        <enable>1</enable>
+        <topLeftX>65536</topLeftX>
+        <topLeftY>65536</topLeftY>
+        <enWatermark>0</enWatermark>
+        <enBgcolor>0</enBgcolor>
+        </OsdChannelName>
+        <OsdDatetime version="1.1">
+        <channelId>0</channelId>
+        <enable>1</enable>
+        <topLeftX>65537</topLeftX>
+        <topLeftY>1</topLeftY>
+        <width>0</width>
+        <height>0</height>
+        <language>Chinese</language>
+        </OsdDatetime>
+        </body>"#
+    );
+
+    let b = BcXml::try_parse(sample.as_bytes()).unwrap();
+    match b {
+        BcXml {
+            osd_channel_name:
+                Some(OsdChannelName {
+                    ref name,
+                    enable: 1,
+                    top_left_x: 65536,
+                    ..
+                }),
+            osd_datetime:
+                Some(OsdDatetime {
+                    enable: 1,
+                    top_left_x: 65537,
+                    top_left_y: 1,
+                    width: Some(0),
+                    ..
+                }),
+            ..
+        } if name == "Cammy02" => {}
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_compression_deser() {
+    let sample = indoc!(
+        r#"
+        <?xml version="1.0" encoding="UTF-8" ?>
+        <body>
+        <Compression version="1.1">
+        <channelId>0</channelId>
+        <isNoTranslateFrame>1</isNoTranslateFrame>
+        <mainStream>
+        <audio>1</audio>
+        <resolutionName>2304*1296</resolutionName>
+        <width>2304</width>
+        <height>1296</height>
+        <encoderType>cbr</encoderType>
+        <frame>15</frame>
+        <bitRate>2560</bitRate>
+        <encoderProfile>high</encoderProfile>
+        </mainStream>
+        <subStream>
+        <audio>1</audio>
+        <resolutionName>896*512</resolutionName>
+        <width>896</width>
+        <height>512</height>
+        <encoderType>cbr</encoderType>
+        <frame>15</frame>
+        <bitRate>512</bitRate>
+        <encoderProfile>high</encoderProfile>
+        </subStream>
+        <thirdStream>
+        <audio>0</audio>
+        <resolutionName></resolutionName>
+        <width>0</width>
+        <height>0</height>
+        <encoderType>vbr</encoderType>
+        <frame>0</frame>
+        <bitRate>0</bitRate>
+        <encoderProfile>default</encoderProfile>
+        </thirdStream>
+        </Compression>
+        </body>"#
+    );
+
+    let b = BcXml::try_parse(sample.as_bytes()).unwrap();
+    match b {
+        BcXml {
+            compression:
+                Some(Compression {
+                    is_no_translate_frame: Some(1),
+                    main_stream:
+                        StreamCompression {
+                            ref resolution_name,
+                            width: 2304,
+                            height: 1296,
+                            frame: 15,
+                            bit_rate: 2560,
+                            ..
+                        },
+                    sub_stream: StreamCompression { bit_rate: 512, .. },
+                    third_stream: Some(StreamCompression { width: 0, .. }),
+                    ..
+                }),
+            ..
+        } if resolution_name == "2304*1296" => {}
+        _ => panic!(),
+    }
+}
+
 #[test]
 fn test_binary_deser() {
     let _ = env_logger::builder().is_test(true).try_init();
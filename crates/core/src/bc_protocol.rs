@@ -1,30 +1,39 @@
 use crate::{bc, bcmedia};
 use log::*;
 use std::convert::TryInto;
-use std::net::ToSocketAddrs;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::Path;
 use std::sync::atomic::{AtomicU16, Ordering};
 
 use Md5Trunc::*;
 
+mod compression;
 mod connection;
+mod devicename;
 mod errors;
+mod hddinfo;
 mod ledstate;
 mod login;
 mod logout;
 mod motion;
+mod norm;
+mod osd;
 mod ping;
 mod pirstate;
 mod reboot;
+mod record;
 mod resolution;
 mod stream;
+mod support;
 mod talk;
 mod time;
 mod version;
+mod videoinput;
 
 use super::RX_TIMEOUT;
 use bc::model::*;
 pub(crate) use connection::*;
-pub use errors::Error;
+pub use errors::{Error, TalkRejection};
 pub use ledstate::LightState;
 pub use motion::{MotionOutput, MotionOutputError, MotionStatus};
 pub use pirstate::PirState;
@@ -52,6 +61,9 @@ pub struct BcCamera {
     message_num: AtomicU16,
     // Certain commands such as logout require the username/pass in plain text.... why....???
     credentials: Option<Credentials>,
+    // Identifies the camera's audio channel so talks through other connections to the
+    // same camera in this process wait their turn
+    talk_key: String,
 }
 
 // Used for caching the credentials
@@ -116,7 +128,89 @@ impl BcCamera {
     /// returns either an error or the camera
     ///
     pub fn new_with_uid(uid: &str, channel_id: u8) -> Result<Self> {
-        Self::new(SocketAddrOrUid::Uid(uid.to_string()), channel_id)
+        Self::new_with_uid_and_discovery(uid, channel_id, &UdpOptions::default())
+    }
+
+    ///
+    /// Create a new camera interface with this uid and channel ID
+    /// finding and talking to the camera with the given udp options
+    ///
+    /// # Parameters
+    ///
+    /// * `uid` - The uid of the camera
+    ///
+    /// * `channel_id` - The channel ID this is usually zero unless using a NVR
+    ///
+    /// * `options` - The discovery methods, p2p servers, mtu and source ip to use,
+    ///   usually [`UdpOptions::default`]
+    ///
+    /// # Returns
+    ///
+    /// returns either an error or the camera
+    ///
+    pub fn new_with_uid_and_discovery(
+        uid: &str,
+        channel_id: u8,
+        options: &UdpOptions,
+    ) -> Result<Self> {
+        Self::new_with_discovery(SocketAddrOrUid::Uid(uid.to_string()), channel_id, options)
+    }
+
+    ///
+    /// Create a new camera interface that finds the camera from its uid
+    /// but then connects to it over TCP
+    ///
+    /// The discovery is done over UDP the same way as [`BcCamera::new_with_uid_and_discovery`],
+    /// only the ip of the camera is kept. This suits cameras on a dynamic ip
+    /// where UDP streaming is unreliable. Only [`DiscoveryMethod::Local`] is
+    /// useful here, the address from remote discovery is usually not reachable over TCP.
+    ///
+    /// # Parameters
+    ///
+    /// * `uid` - The uid of the camera
+    ///
+    /// * `channel_id` - The channel ID this is usually zero unless using a NVR
+    ///
+    /// * `options` - The discovery methods, p2p servers, mtu and source ip to discover with,
+    ///   usually [`UdpOptions::default`]
+    ///
+    /// * `tcp_port` - The TCP port of the camera, usually 9000
+    ///
+    /// # Returns
+    ///
+    /// returns either an error or the camera
+    ///
+    pub fn new_with_uid_over_tcp(
+        uid: &str,
+        channel_id: u8,
+        options: &UdpOptions,
+        tcp_port: u16,
+    ) -> Result<Self> {
+        let udp_addr = Self::discover_addr(uid, options)?;
+        let addr = SocketAddr::new(udp_addr.ip(), tcp_port);
+        info!("Connecting to {} over TCP at {}", uid, addr);
+        Self::new_with_discovery(SocketAddrOrUid::SocketAddr(addr), channel_id, options)
+    }
+
+    ///
+    /// Find the address of the camera with this uid without connecting to it
+    ///
+    /// The discovery is the same as [`BcCamera::new_with_uid_and_discovery`]. The port of
+    /// the returned address is the camera's udp port, only the ip is of use to connect over TCP
+    ///
+    /// # Parameters
+    ///
+    /// * `uid` - The uid of the camera
+    ///
+    /// * `options` - The discovery methods, p2p servers, mtu and source ip to discover with,
+    ///   usually [`UdpOptions::default`]
+    ///
+    /// # Returns
+    ///
+    /// returns either an error or the address of the camera
+    ///
+    pub fn discover_addr(uid: &str, options: &UdpOptions) -> Result<SocketAddr> {
+        Ok(UdpSource::discover_addr(uid, RX_TIMEOUT, options)?)
     }
 
     ///
@@ -167,26 +261,41 @@ impl BcCamera {
     /// returns either an error or the camera
     ///
     pub fn new(addr: SocketAddrOrUid, channel_id: u8) -> Result<Self> {
+        Self::new_with_discovery(addr, channel_id, &UdpOptions::default())
+    }
+
+    fn new_with_discovery(
+        addr: SocketAddrOrUid,
+        channel_id: u8,
+        options: &UdpOptions,
+    ) -> Result<Self> {
+        let talk_key = match &addr {
+            SocketAddrOrUid::SocketAddr(addr) => format!("{}/{}", addr, channel_id),
+            SocketAddrOrUid::Uid(uid) => format!("{}/{}", uid, channel_id),
+        };
         let source = match addr {
             SocketAddrOrUid::SocketAddr(addr) => {
                 debug!("Trying address {}", addr);
-                BcSource::new_tcp(addr, RX_TIMEOUT)?
+                let source = BcSource::new_tcp(addr, RX_TIMEOUT)?;
+                info!("Connected to {} via TCP", addr);
+                source
             }
             SocketAddrOrUid::Uid(uid) => {
                 debug!("Trying uid {}", uid);
-                BcSource::new_udp(&uid, RX_TIMEOUT)?
+                let source = BcSource::new_udp(&uid, RX_TIMEOUT, options)?;
+                info!("Connected to {} via UDP", uid);
+                source
             }
         };
 
         let conn = BcConnection::new(source)?;
-
-        debug!("Success");
         let me = Self {
             connection: Some(conn),
             message_num: AtomicU16::new(0),
             channel_id,
             logged_in: false,
             credentials: None,
+            talk_key,
         };
 
         if let Some(conn) = &me.connection {
@@ -211,6 +320,30 @@ impl BcCamera {
         Ok(me)
     }
 
+    ///
+    /// Dump the raw bc packets of this connection into the given directory
+    ///
+    /// All packets sent and received after this call are written to
+    /// `<name>-<unix time>-<n>-sent.bin` and `<name>-<unix time>-<n>-recv.bin`, where `n`
+    /// keeps the files of each connection apart.
+    /// This is intended for debugging the protocol and the received file can be
+    /// replayed through the deserializer
+    ///
+    /// # Parameters
+    ///
+    /// * `dir` - The directory to write the dumps into, it must already exist
+    ///
+    /// * `name` - The prefix of the file names, usually the camera name
+    ///
+    pub fn dump_packets(&self, dir: &Path, name: &str) -> Result<()> {
+        let connection = self
+            .connection
+            .as_ref()
+            .expect("Must be connected to dump packets");
+        connection.set_packet_dump(PacketDump::new(dir, name)?);
+        Ok(())
+    }
+
     /// This method will get a new message number and increment the message count atomically
     pub fn new_message_num(&self) -> u16 {
         self.message_num.fetch_add(1, Ordering::Relaxed)
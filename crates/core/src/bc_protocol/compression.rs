@@ -0,0 +1,95 @@
+use super::{BcCamera, Error, Result, RX_TIMEOUT};
+use crate::bc::{model::*, xml::*};
+
+impl BcCamera {
+    /// Get the [Compression] xml which contains the resolution, bitrate and fps of the streams
+    pub fn get_compression(&self) -> Result<Compression> {
+        let connection = self
+            .connection
+            .as_ref()
+            .expect("Must be connected to get the compression");
+        let sub_get = connection.subscribe(MSG_ID_GET_COMPRESSION)?;
+        let get = Bc {
+            meta: BcMeta {
+                msg_id: MSG_ID_GET_COMPRESSION,
+                channel_id: self.channel_id,
+                msg_num: self.new_message_num(),
+                response_code: 0,
+                stream_type: 0,
+                class: 0x6414,
+            },
+            body: BcBody::ModernMsg(ModernMsg {
+                extension: Some(Extension {
+                    channel_id: Some(self.channel_id),
+                    ..Default::default()
+                }),
+                payload: None,
+            }),
+        };
+
+        sub_get.send(get)?;
+        let msg = sub_get.rx.recv_timeout(RX_TIMEOUT)?;
+
+        if let BcBody::ModernMsg(ModernMsg {
+            payload:
+                Some(BcPayloads::BcXml(BcXml {
+                    compression: Some(compression),
+                    ..
+                })),
+            ..
+        }) = msg.body
+        {
+            Ok(compression)
+        } else {
+            Err(Error::UnintelligibleReply {
+                reply: msg,
+                why: "Expected Compression xml but it was not recieved",
+            })
+        }
+    }
+
+    /// Set the resolution, bitrate and fps of the streams using the [Compression] xml
+    pub fn set_compression(&self, compression: Compression) -> Result<()> {
+        let connection = self
+            .connection
+            .as_ref()
+            .expect("Must be connected to set the compression");
+        let sub_set = connection.subscribe(MSG_ID_SET_COMPRESSION)?;
+
+        let set = Bc {
+            meta: BcMeta {
+                msg_id: MSG_ID_SET_COMPRESSION,
+                channel_id: self.channel_id,
+                msg_num: self.new_message_num(),
+                response_code: 0,
+                stream_type: 0,
+                class: 0x6414,
+            },
+            body: BcBody::ModernMsg(ModernMsg {
+                extension: Some(Extension {
+                    channel_id: Some(self.channel_id),
+                    ..Default::default()
+                }),
+                payload: Some(BcPayloads::BcXml(BcXml {
+                    compression: Some(compression),
+                    ..Default::default()
+                })),
+            }),
+        };
+
+        sub_set.send(set)?;
+        let msg = sub_set.rx.recv_timeout(RX_TIMEOUT)?;
+
+        if let BcMeta {
+            response_code: 200, ..
+        } = msg.meta
+        {
+            Ok(())
+        } else {
+            Err(Error::UnintelligibleReply {
+                reply: msg,
+                why: "The camera did not accept the Compression xml",
+            })
+        }
+    }
+}
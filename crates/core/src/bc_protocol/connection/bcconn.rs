@@ -1,4 +1,5 @@
-use super::{BcSource, BcSubscription, Error, Result, TcpSource};
+use super::packetdump::DumpReader;
+use super::{BcSource, BcSubscription, Error, PacketDump, Result, TcpSource};
 use crate::bc;
 use crate::bc::model::*;
 use log::*;
@@ -27,6 +28,7 @@ pub struct BcConnection {
     encryption_protocol: Arc<Mutex<EncryptionProtocol>>,
     poll_abort: Arc<AtomicBool>,
     keep_alive_msg: Arc<Mutex<Option<Bc>>>,
+    packet_dump: Arc<Mutex<Option<Arc<PacketDump>>>>,
 }
 
 impl BcConnection {
@@ -39,9 +41,11 @@ impl BcConnection {
         let connections_encryption_protocol = encryption_protocol.clone();
         let poll_abort = Arc::new(AtomicBool::new(false));
         let poll_abort_rx = poll_abort.clone();
-        let mut conn = source.try_clone()?;
+        let conn = source.try_clone()?;
         let keep_alive_msg: Arc<Mutex<Option<Bc>>> = Arc::new(Mutex::new(None));
         let connections_keep_alive_msg = keep_alive_msg.clone();
+        let packet_dump: Arc<Mutex<Option<Arc<PacketDump>>>> = Arc::new(Mutex::new(None));
+        let connections_packet_dump = packet_dump.clone();
         let rx_thread = std::thread::spawn(move || {
             let keep_alive_encryption_protocol = connections_encryption_protocol.clone();
             let mut context = BcContext::new(connections_encryption_protocol);
@@ -49,7 +53,13 @@ impl BcConnection {
             let mut last_keep_alive = Instant::now();
             let keep_alive_time = Duration::from_millis(500);
             loop {
-                result = Self::poll(&mut context, &conn, &mut subs, &connections_keep_alive_msg);
+                let dump = connections_packet_dump.lock().unwrap().clone();
+                result = Self::poll(
+                    &mut context,
+                    DumpReader::new(&conn, dump.as_deref()),
+                    &mut subs,
+                    &connections_keep_alive_msg,
+                );
                 if poll_abort_rx.load(Ordering::Relaxed) {
                     break; // Poll has been aborted by request usally during disconnect
                 }
@@ -67,9 +77,15 @@ impl BcConnection {
                     last_keep_alive = Instant::now();
                     if let Ok(lock) = connections_keep_alive_msg.try_lock() {
                         if let Some(keep_alive_msg) = lock.as_ref() {
-                            let _ = keep_alive_msg
-                                .serialize(&conn, &keep_alive_encryption_protocol.lock().unwrap());
-                            let _ = conn.flush();
+                            if let Ok(buf) = keep_alive_msg
+                                .serialize(vec![], &keep_alive_encryption_protocol.lock().unwrap())
+                            {
+                                if let Some(dump) = dump.as_ref() {
+                                    let _ = dump.sent(&buf);
+                                }
+                                let _ = (&conn).write_all(&buf);
+                                let _ = (&conn).flush();
+                            }
                         }
                     }
                 }
@@ -83,6 +99,7 @@ impl BcConnection {
             encryption_protocol,
             poll_abort,
             keep_alive_msg,
+            packet_dump,
         })
     }
 
@@ -91,7 +108,13 @@ impl BcConnection {
     }
 
     pub(super) fn send(&self, bc: Bc) -> Result<()> {
-        bc.serialize(&*self.sink.lock().unwrap(), &self.get_encrypted())?;
+        let buf = bc.serialize(vec![], &self.get_encrypted())?;
+        if let Some(dump) = self.packet_dump.lock().unwrap().as_ref() {
+            if let Err(e) = dump.sent(&buf) {
+                warn!("Failed to dump sent packets: {}", e);
+            }
+        }
+        (&*self.sink.lock().unwrap()).write_all(&buf)?;
         let _ = self.sink.lock().unwrap().flush();
         Ok(())
     }
@@ -114,6 +137,11 @@ impl BcConnection {
         *self.keep_alive_msg.lock().unwrap() = Some(msg);
     }
 
+    /// Copy all bc packets sent and received from now on into the given dump
+    pub fn set_packet_dump(&self, dump: PacketDump) {
+        *self.packet_dump.lock().unwrap() = Some(Arc::new(dump));
+    }
+
     pub fn set_encrypted(&self, value: EncryptionProtocol) {
         *(self.encryption_protocol.lock().unwrap()) = value;
     }
@@ -126,9 +154,13 @@ impl BcConnection {
         self.sink.lock().unwrap().is_udp()
     }
 
+    pub fn udp_port(&self) -> Option<u16> {
+        self.sink.lock().unwrap().udp_port()
+    }
+
     fn poll(
         context: &mut BcContext,
-        connection: &BcSource,
+        connection: impl Read,
         subscribers: &mut Arc<Mutex<BTreeMap<u32, Sender<Bc>>>>,
         connections_keep_alive_msg: &Arc<Mutex<Option<Bc>>>,
     ) -> Result<()> {
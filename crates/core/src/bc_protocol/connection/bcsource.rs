@@ -1,4 +1,5 @@
 use super::{Result, TcpSource, UdpSource};
+use crate::bc_protocol::UdpOptions;
 use std::io::{Error as IoError, ErrorKind, Read, Write};
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
@@ -21,11 +22,19 @@ impl BcSource {
         Ok(BcSource::Tcp(Mutex::new(source)))
     }
 
-    pub fn new_udp(uid: &str, timeout: Duration) -> Result<Self> {
-        let source = UdpSource::new(uid, timeout)?;
+    pub fn new_udp(uid: &str, timeout: Duration, options: &UdpOptions) -> Result<Self> {
+        let source = UdpSource::new(uid, timeout, options)?;
         Ok(BcSource::Udp(Mutex::new(source)))
     }
 
+    /// The local udp port of the connection or `None` for tcp
+    pub fn udp_port(&self) -> Option<u16> {
+        match self {
+            BcSource::Tcp(_) => None,
+            BcSource::Udp(source) => Some(source.lock().unwrap().local_port()),
+        }
+    }
+
     pub fn try_clone(&self) -> IoResult<Self> {
         match self {
             BcSource::Tcp(source) => match &mut source.try_lock() {
@@ -26,12 +26,13 @@ mod bcsource;
 mod bcsub;
 mod binarysub;
 mod filesub;
+mod packetdump;
 mod tcpconn;
 mod udpconn;
 
 pub(crate) use self::{
     bcconn::BcConnection, bcsource::BcSource, bcsub::BcSubscription, binarysub::BinarySubscriber,
-    filesub::FileSubscriber, tcpconn::TcpSource, udpconn::UdpSource,
+    filesub::FileSubscriber, packetdump::PacketDump, tcpconn::TcpSource, udpconn::UdpSource,
 };
 
 #[derive(Debug, Error)]
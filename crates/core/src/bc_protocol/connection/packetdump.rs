@@ -0,0 +1,149 @@
+use log::*;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Read, Result as IoResult, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use time::OffsetDateTime;
+
+// Numbers the dumps so that connections opened in the same second, such as the
+// main and sub stream of a camera, do not write into each others files
+static NEXT_DUMP_NUM: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes a copy of the raw bytes that pass over a connection to disk
+///
+/// Two files are created in the dump directory, one for the bytes
+/// received from the camera and one for the bytes sent to it. They
+/// are named `<name>-<unix time>-<n>-recv.bin` and `<name>-<unix time>-<n>-sent.bin`
+/// where `n` numbers the dumps of this process. Existing files are never overwritten
+///
+/// The bytes are those of the bc packets after any udp reassembly
+/// so they can be read back with a [`FileSubscriber`](super::FileSubscriber)
+pub(crate) struct PacketDump {
+    recv: Mutex<File>,
+    sent: Mutex<File>,
+}
+
+impl PacketDump {
+    pub(crate) fn new(dir: &Path, name: &str) -> IoResult<Self> {
+        let stamp = OffsetDateTime::now_utc().unix_timestamp();
+        loop {
+            let num = NEXT_DUMP_NUM.fetch_add(1, Ordering::Relaxed);
+            let prefix = format!("{}-{}-{}", name, stamp, num);
+            // Files left by another process with the same name are skipped
+            let recv_path = dir.join(format!("{}-recv.bin", prefix));
+            let recv = match create_new(&recv_path) {
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                result => result?,
+            };
+            // The dump is only kept as a pair, so the recv file is removed when the
+            // sent file cannot be made
+            let sent = match create_new(&dir.join(format!("{}-sent.bin", prefix))) {
+                Ok(sent) => sent,
+                Err(e) => {
+                    drop(recv);
+                    let _ = std::fs::remove_file(&recv_path);
+                    if e.kind() == ErrorKind::AlreadyExists {
+                        continue;
+                    }
+                    return Err(e);
+                }
+            };
+            return Ok(Self {
+                recv: Mutex::new(recv),
+                sent: Mutex::new(sent),
+            });
+        }
+    }
+
+    pub(crate) fn recv(&self, buf: &[u8]) -> IoResult<()> {
+        self.recv.lock().unwrap().write_all(buf)
+    }
+
+    pub(crate) fn sent(&self, buf: &[u8]) -> IoResult<()> {
+        self.sent.lock().unwrap().write_all(buf)
+    }
+}
+
+fn create_new(path: &Path) -> IoResult<File> {
+    OpenOptions::new().write(true).create_new(true).open(path)
+}
+
+/// A reader that copies everything read from the inner reader into
+/// the recv file of the packet dump (when there is one)
+pub(crate) struct DumpReader<'a, R: Read> {
+    inner: R,
+    dump: Option<&'a PacketDump>,
+}
+
+impl<'a, R: Read> DumpReader<'a, R> {
+    pub(crate) fn new(inner: R, dump: Option<&'a PacketDump>) -> Self {
+        Self { inner, dump }
+    }
+}
+
+impl<'a, R: Read> Read for DumpReader<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        let read = self.inner.read(buf)?;
+        if let Some(dump) = self.dump {
+            if let Err(e) = dump.recv(&buf[..read]) {
+                warn!("Failed to dump received packets: {}", e);
+            }
+        }
+        Ok(read)
+    }
+}
+
+#[test]
+fn test_dumps_do_not_collide() {
+    let dir = std::env::temp_dir().join(format!("neolink-dump-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Two connections to the same camera in the same second
+    let first = PacketDump::new(&dir, "Camera").unwrap();
+    first.recv(b"first").unwrap();
+    let second = PacketDump::new(&dir, "Camera").unwrap();
+    second.recv(b"second").unwrap();
+    drop(first);
+    drop(second);
+
+    let mut contents: Vec<Vec<u8>> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.to_string_lossy().ends_with("-recv.bin"))
+        .map(|path| std::fs::read(path).unwrap())
+        .collect();
+    contents.sort();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(contents, vec![b"first".to_vec(), b"second".to_vec()]);
+}
+
+#[test]
+fn test_dump_without_sent_file_leaves_no_recv() {
+    let dir = std::env::temp_dir().join(format!("neolink-dump-pair-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Only the sent files of the next few dumps are already taken
+    let stamp = OffsetDateTime::now_utc().unix_timestamp();
+    let next = NEXT_DUMP_NUM.load(Ordering::Relaxed);
+    for stamp in stamp..stamp + 2 {
+        for num in next..next + 3 {
+            File::create(dir.join(format!("Camera-{}-{}-sent.bin", stamp, num))).unwrap();
+        }
+    }
+
+    let dump = PacketDump::new(&dir, "Camera").unwrap();
+    drop(dump);
+
+    let names: Vec<String> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let recv: Vec<&String> = names.iter().filter(|n| n.ends_with("-recv.bin")).collect();
+    assert_eq!(recv.len(), 1);
+    let sent = recv[0].replace("-recv.bin", "-sent.bin");
+    assert!(names.contains(&sent));
+}
@@ -1,4 +1,5 @@
-use super::{aborthandle::AbortHandle, Error, Result, MTU, P2P_RELAY_HOSTNAMES, WAIT_TIME};
+use super::{aborthandle::AbortHandle, Error, Result, WAIT_TIME};
+use crate::bc_protocol::{DiscoveryMethod, UdpOptions};
 use crate::bcudp::{model::*, xml::*};
 use local_ip_address::local_ip;
 use log::*;
@@ -81,7 +82,12 @@ impl UdpDiscover {
     // This involves broadcasting a C2dC
     // Bc Discovery packet to ports 2015 and 2018
     // and awaiting a D2cCr reply
-    fn discover_from_uuid_local(socket: &UdpSocket, uid: &str, timeout: Duration) -> Result<Self> {
+    fn discover_from_uuid_local(
+        socket: &UdpSocket,
+        uid: &str,
+        timeout: Duration,
+        mtu: u32,
+    ) -> Result<Self> {
         let mut rng = thread_rng();
         // If tid is too large it will overflow during encrypt so we just use a random u8
         let tid: u32 = (rng.gen::<u8>()) as u32;
@@ -89,8 +95,6 @@ impl UdpDiscover {
         let local_addr = socket.local_addr()?;
         let port = local_addr.port();
 
-        let mtu = MTU;
-
         let msg = BcUdp::Discovery(UdpDiscovery {
             tid,
             payload: UdpXml {
@@ -191,14 +195,14 @@ impl UdpDiscover {
         socket: &UdpSocket,
         timeout: Duration,
         tid: u32,
+        relay_hostnames: &[String],
+        mtu: u32,
         // client_id: u32,
     ) -> Result<M2cQr> {
         // let local_addr = socket.local_addr()?;
         // let local_port = local_addr.port();
 
-        let mtu = MTU;
-
-        for p2p_relay in P2P_RELAY_HOSTNAMES.iter() {
+        for p2p_relay in relay_hostnames.iter() {
             debug!("Trying register: {}", p2p_relay);
             let msg = BcUdp::Discovery(UdpDiscovery {
                 tid,
@@ -282,6 +286,8 @@ impl UdpDiscover {
         socket: &UdpSocket,
         uid: &str,
         timeout: Duration,
+        relay_hostnames: &[String],
+        mtu: u32,
     ) -> Result<UdpDiscover> {
         let local_addr = socket.local_addr()?;
         let local_port = local_addr.port();
@@ -289,10 +295,9 @@ impl UdpDiscover {
         let mut rng = thread_rng();
         // If tid is too large it will overflow during encrypt so we just use a random u8
         let tid: u32 = (rng.gen::<u8>()) as u32;
-        let mtu = MTU;
         let client_id: i32 = rng.gen();
 
-        let m2c_q_r = Self::get_register(uid, socket, timeout, tid)?;
+        let m2c_q_r = Self::get_register(uid, socket, timeout, tid, relay_hostnames, mtu)?;
 
         debug!("Got this information from the register: {:?}", m2c_q_r);
 
@@ -301,7 +306,12 @@ impl UdpDiscover {
         let log_address = m2c_q_r.log;
         // let device_address = m2c_q_r.t;
 
-        let default_local_address = local_ip().expect("There to be a local ip");
+        // When the socket is bound to a source ip register that one, otherwise guess
+        let default_local_address = if local_addr.ip().is_unspecified() {
+            local_ip().expect("There to be a local ip")
+        } else {
+            local_addr.ip()
+        };
         debug!("Register address found: {:?}", register_address);
         debug!("Registering this address: {:?}", default_local_address);
 
@@ -531,20 +541,44 @@ impl UdpDiscover {
         })
     }
 
+    // Tries each discovery method in turn, moving on to the next one when it times out
     pub fn discover_from_uuid(
         socket: &UdpSocket,
         uid: &str,
         timeout: Duration,
-        allow_remote: bool,
+        options: &UdpOptions,
     ) -> Result<Self> {
-        match Self::discover_from_uuid_local(socket, uid, timeout) {
-            Err(Error::Timeout) if allow_remote => {
-                info!("Trying remote discovery against reolink servers");
-                Self::discover_from_uuid_remote(socket, uid, timeout)
+        let mtu = options.mtu;
+        for method in &options.discovery {
+            let (result, via) = match method {
+                DiscoveryMethod::Local => (
+                    Self::discover_from_uuid_local(socket, uid, timeout, mtu),
+                    "local broadcast",
+                ),
+                DiscoveryMethod::Remote => {
+                    info!("Trying remote discovery against reolink servers");
+                    (
+                        Self::discover_from_uuid_remote(
+                            socket,
+                            uid,
+                            timeout,
+                            &options.relay_hostnames,
+                            mtu,
+                        ),
+                        "the reolink servers",
+                    )
+                }
+            };
+            match result {
+                Ok(result) => {
+                    info!("Discovered {} at {} via {}", uid, result.address, via);
+                    return Ok(result);
+                }
+                Err(Error::Timeout) => debug!("Discovery of {} via {} timed out", uid, via),
+                Err(e) => return Err(e),
             }
-            Ok(result) => Ok(result),
-            Err(e) => Err(e),
         }
+        Err(Error::Timeout)
     }
 
     pub fn send_client_disconnect(&self, socket: &UdpSocket) {
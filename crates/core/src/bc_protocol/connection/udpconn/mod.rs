@@ -9,13 +9,13 @@
 /// this includes the BcUdp wrapping and the acknoledgements
 ///
 use super::{Error, Result};
+use crate::bc_protocol::UdpOptions;
 use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender};
-use lazy_static::lazy_static;
 use log::*;
 use rand::{seq::SliceRandom, thread_rng, Rng};
 use std::{
     io::{BufRead, Error as IoError, ErrorKind, Read, Result as IoResult, Write},
-    net::{SocketAddr, UdpSocket},
+    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
     sync::Arc,
     time::Duration,
 };
@@ -34,25 +34,6 @@ use transmit::*;
 const SOCKET_WAIT_TIME: Duration = Duration::from_millis(50);
 // How long to wait between retransmits when no reply is recieved
 const WAIT_TIME: Duration = Duration::from_millis(500);
-// The maximum data size including header
-//
-// TODO: Maybe use path mtu discovery (although reolinks seems to just use this constant)
-const MTU: u32 = 1030;
-
-lazy_static! {
-    static ref P2P_RELAY_HOSTNAMES: [&'static str; 10] = [
-        "p2p.reolink.com",
-        "p2p1.reolink.com",
-        "p2p2.reolink.com",
-        "p2p3.reolink.com",
-        "p2p6.reolink.com",
-        "p2p7.reolink.com",
-        "p2p8.reolink.com",
-        "p2p9.reolink.com",
-        "p2p14.reolink.com",
-        "p2p15.reolink.com",
-    ];
-}
 
 pub struct UdpSource {
     outgoing: Sender<Vec<u8>>,
@@ -60,46 +41,62 @@ pub struct UdpSource {
     aborter: AbortHandle,
     timeout: Duration,
     mtu: u32,
+    local_port: u16,
 
     read_buffer: Buffered,
     write_buffer: Buffered,
 }
 
 impl UdpSource {
-    pub fn new(uid: &str, timeout: Duration) -> Result<Self> {
+    pub fn new(uid: &str, timeout: Duration, options: &UdpOptions) -> Result<Self> {
         let (outgoing, from_outgoing) = unbounded();
         let (to_incoming, incoming) = unbounded();
         let aborter = AbortHandle::new();
 
-        Self::start_polling(uid, timeout, &aborter, to_incoming, from_outgoing)?;
+        let socket = Self::get_socket(SOCKET_WAIT_TIME, options.source_ip)?;
+        let local_port = socket.local_addr()?.port();
+        let discovery_result = UdpDiscover::discover_from_uuid(&socket, uid, timeout, options)?;
+
+        Self::start_polling(
+            socket,
+            discovery_result,
+            &aborter,
+            to_incoming,
+            from_outgoing,
+        )?;
 
         Ok(Self {
             outgoing,
             incoming,
             aborter,
             timeout,
-            mtu: MTU,
+            mtu: options.mtu,
+            local_port,
 
             read_buffer: Default::default(),
             write_buffer: Default::default(),
         })
     }
 
+    /// Finds the address of the camera without keeping the udp connection
+    ///
+    /// This is used to connect over tcp to a camera whose ip is not fixed
+    pub fn discover_addr(uid: &str, timeout: Duration, options: &UdpOptions) -> Result<SocketAddr> {
+        let socket = Self::get_socket(SOCKET_WAIT_TIME, options.source_ip)?;
+        let discovery_result = UdpDiscover::discover_from_uuid(&socket, uid, timeout, options)?;
+        // Close the session that discovery opened so the camera isn't left waiting on it
+        discovery_result.send_client_disconnect(&socket);
+        Ok(discovery_result.address)
+    }
+
     fn start_polling(
-        uid: &str,
-        timeout: Duration,
+        socket: UdpSocket,
+        discovery_result: UdpDiscover,
         aborter: &AbortHandle,
         to_incoming: Sender<Vec<u8>>,
         from_outgoing: Receiver<Vec<u8>>,
     ) -> Result<()> {
-        let socket = Self::get_socket(SOCKET_WAIT_TIME)?;
-        let allow_remote = true;
-        let discovery_result = Arc::new(UdpDiscover::discover_from_uuid(
-            &socket,
-            uid,
-            timeout,
-            allow_remote,
-        )?);
+        let discovery_result = Arc::new(discovery_result);
         socket.connect(discovery_result.address)?;
         let transmit = Arc::new(UdpTransmit::new());
 
@@ -175,6 +172,7 @@ impl UdpSource {
             aborter: self.aborter.clone(),
             timeout: self.timeout,
             mtu: self.mtu,
+            local_port: self.local_port,
 
             // New buffer so they don't pollute each other
             read_buffer: Default::default(),
@@ -182,11 +180,17 @@ impl UdpSource {
         })
     }
 
+    /// The local port of the udp socket used to talk to the camera
+    pub fn local_port(&self) -> u16 {
+        self.local_port
+    }
+
     fn stop_polling(&self) {
         self.aborter.abort();
     }
 
-    fn get_socket(timeout: Duration) -> Result<UdpSocket> {
+    // Binds to all interfaces unless a source ip is given
+    fn get_socket(timeout: Duration, source_ip: Option<IpAddr>) -> Result<UdpSocket> {
         // Select a random port to bind to
         let mut ports: Vec<u16> = (53500..54000).into_iter().collect();
         let mut rng = thread_rng();
@@ -194,7 +198,9 @@ impl UdpSource {
 
         let addrs: Vec<_> = ports
             .iter()
-            .map(|&port| SocketAddr::from(([0, 0, 0, 0], port)))
+            .map(|&port| {
+                SocketAddr::new(source_ip.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)), port)
+            })
             .collect();
         let socket = UdpSocket::bind(&addrs[..])?;
         socket.set_read_timeout(Some(timeout))?;
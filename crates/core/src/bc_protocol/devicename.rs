@@ -0,0 +1,108 @@
+use super::{BcCamera, Error, Result, RX_TIMEOUT};
+use crate::bc::{model::*, xml::*};
+
+impl BcCamera {
+    ///
+    /// Get the name the camera reports for itself
+    ///
+    /// This is the name shown in the Reolink app
+    ///
+    /// # Returns
+    ///
+    /// returns either an error or the device name
+    ///
+    pub fn get_device_name(&self) -> Result<String> {
+        let connection = self
+            .connection
+            .as_ref()
+            .expect("Must be connected to get the device name");
+        let sub_get_general = connection.subscribe(MSG_ID_GET_GENERAL)?;
+        let get = Bc {
+            meta: BcMeta {
+                msg_id: MSG_ID_GET_GENERAL,
+                channel_id: self.channel_id,
+                msg_num: self.new_message_num(),
+                response_code: 0,
+                stream_type: 0,
+                class: 0x6414,
+            },
+            body: BcBody::ModernMsg(ModernMsg::default()),
+        };
+
+        sub_get_general.send(get)?;
+        let msg = sub_get_general.rx.recv_timeout(RX_TIMEOUT)?;
+
+        if let BcBody::ModernMsg(ModernMsg {
+            payload:
+                Some(BcPayloads::BcXml(BcXml {
+                    system_general:
+                        Some(SystemGeneral {
+                            device_name: Some(device_name),
+                            ..
+                        }),
+                    ..
+                })),
+            ..
+        }) = msg.body
+        {
+            Ok(device_name)
+        } else {
+            Err(Error::UnintelligibleReply {
+                reply: msg,
+                why: "Reply did not contain SystemGeneral with a device name",
+            })
+        }
+    }
+
+    ///
+    /// Set the name the camera reports for itself
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The new name of the camera
+    ///
+    /// # Returns
+    ///
+    /// returns Ok(()) or error
+    ///
+    pub fn set_device_name(&self, name: &str) -> Result<()> {
+        let connection = self
+            .connection
+            .as_ref()
+            .expect("Must be connected to set the device name");
+        let sub_set_general = connection.subscribe(MSG_ID_SET_GENERAL)?;
+        let set = Bc::new_from_xml(
+            BcMeta {
+                msg_id: MSG_ID_SET_GENERAL,
+                channel_id: self.channel_id,
+                msg_num: self.new_message_num(),
+                response_code: 0,
+                stream_type: 0,
+                class: 0x6414,
+            },
+            BcXml {
+                system_general: Some(SystemGeneral {
+                    version: xml_ver(),
+                    device_name: Some(name.to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        );
+
+        sub_set_general.send(set)?;
+        let msg = sub_set_general.rx.recv_timeout(RX_TIMEOUT)?;
+
+        if let BcMeta {
+            response_code: 200, ..
+        } = msg.meta
+        {
+            Ok(())
+        } else {
+            Err(Error::UnintelligibleReply {
+                reply: msg,
+                why: "The camera did not accept the new device name",
+            })
+        }
+    }
+}
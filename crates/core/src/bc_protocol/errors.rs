@@ -1,12 +1,13 @@
 use super::bc::model::Bc;
 use err_derive::Error;
+use std::fmt;
 
 /// This is the primary error type of the library
 #[derive(Debug, Error)]
 #[allow(clippy::large_enum_variant)]
 pub enum Error {
     /// Error raised when IO fails such as when the connection is lost
-    #[error(display = "Communication error")]
+    #[error(display = "Communication error, the camera may be unreachable")]
     Communication(#[error(source)] std::io::Error),
 
     /// Error raised during deserlization
@@ -30,7 +31,10 @@ pub enum Error {
     ConnectionError(#[error(source)] super::connection::Error),
 
     /// Raised when a Bc reply was not understood
-    #[error(display = "Communication error")]
+    #[error(
+        display = "Unexpected reply from the camera, the feature may be unsupported: {}",
+        why
+    )]
     UnintelligibleReply {
         /// The Bc packet that was not understood
         reply: Bc,
@@ -43,15 +47,15 @@ pub enum Error {
     DroppedConnection(#[error(source)] std::sync::mpsc::RecvError),
 
     /// Raised when the RX_TIMEOUT is reach
-    #[error(display = "Timeout")]
+    #[error(display = "Timed out waiting for the camera")]
     Timeout,
 
     /// Raised when connection is dropped because the timeout is reach
-    #[error(display = "Dropped connection")]
+    #[error(display = "Dropped connection, the camera stopped responding")]
     TimeoutDisconnected,
 
-    /// Raised when failed to login to the camera
-    #[error(display = "Credential error")]
+    /// Raised when the camera rejects the username or password during login
+    #[error(display = "Authentication failed, check the username and password")]
     AuthFailed,
 
     /// Raised when the given camera url could not be resolved
@@ -62,11 +66,95 @@ pub enum Error {
     #[error(display = "Talk data is not ADPCM")]
     UnknownTalkEncoding,
 
+    /// Raised when the camera refuses a talk, either its config or the audio itself
+    #[error(display = "The camera rejected the talk with {}", _0)]
+    TalkRejected(TalkRejection),
+
     /// A generic catch all error
-    #[error(display = "Other error")]
+    #[error(display = "Other error: {}", _0)]
     Other(&'static str),
 
     /// A generic catch all error
-    #[error(display = "Other error")]
+    #[error(display = "Other error: {}", _0)]
     OtherString(String),
 }
+
+/// The status code a camera refused a talk with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TalkRejection(pub u16);
+
+impl TalkRejection {
+    /// The camera replies 422 while another client is talking, this clears once
+    /// that talk finishes so the talk is worth retrying
+    pub fn is_busy(&self) -> bool {
+        self.0 == 422
+    }
+}
+
+impl fmt::Display for TalkRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_busy() {
+            write!(f, "code {}, the camera is busy with another talk", self.0)
+        } else {
+            write!(
+                f,
+                "code {}, the camera may not support talk in this audio format",
+                self.0
+            )
+        }
+    }
+}
+
+impl Error {
+    /// A numeric code for the kind of error
+    ///
+    /// The codes are stable between releases, a code is never reused for
+    /// a different kind of error. This makes them suitable for scripts
+    /// and for matching in other languages
+    pub fn code(&self) -> u32 {
+        match self {
+            Error::Communication(_) => 1,
+            Error::Deserialization(_) => 2,
+            Error::Serialization(_) => 3,
+            Error::MediaDeserialization(_) => 4,
+            Error::MediaSerialization(_) => 5,
+            Error::ConnectionError(_) => 6,
+            Error::UnintelligibleReply { .. } => 7,
+            Error::DroppedConnection(_) => 8,
+            Error::Timeout => 9,
+            Error::TimeoutDisconnected => 10,
+            Error::AuthFailed => 11,
+            Error::AddrResolutionError => 12,
+            Error::UnknownTalkEncoding => 13,
+            Error::Other(_) => 14,
+            Error::OtherString(_) => 15,
+            Error::TalkRejected(_) => 16,
+        }
+    }
+}
+
+#[test]
+fn test_error_code_display() {
+    let e = Error::AuthFailed;
+    assert_eq!(e.code(), 11);
+    assert_eq!(
+        e.to_string(),
+        "Authentication failed, check the username and password"
+    );
+
+    let e = Error::Other("No data");
+    assert_eq!(e.code(), 14);
+    assert_eq!(e.to_string(), "Other error: No data");
+
+    let e = Error::TalkRejected(TalkRejection(422));
+    assert_eq!(e.code(), 16);
+    assert_eq!(
+        e.to_string(),
+        "The camera rejected the talk with code 422, the camera is busy with another talk"
+    );
+    let e = Error::TalkRejected(TalkRejection(400));
+    assert_eq!(
+        e.to_string(),
+        "The camera rejected the talk with code 400, the camera may not support talk in this audio format"
+    );
+}
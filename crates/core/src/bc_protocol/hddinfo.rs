@@ -0,0 +1,46 @@
+use super::{BcCamera, Error, Result, RX_TIMEOUT};
+use crate::bc::{model::*, xml::*};
+
+impl BcCamera {
+    /// Request the [HddInfoList] xml which describes the storage of the camera
+    pub fn get_hdd_info_list(&self) -> Result<HddInfoList> {
+        let connection = self
+            .connection
+            .as_ref()
+            .expect("Must be connected to get the storage info");
+        let sub_get = connection.subscribe(MSG_ID_GET_HDD_INFO)?;
+        let get = Bc {
+            meta: BcMeta {
+                msg_id: MSG_ID_GET_HDD_INFO,
+                channel_id: self.channel_id,
+                msg_num: self.new_message_num(),
+                response_code: 0,
+                stream_type: 0,
+                class: 0x6414,
+            },
+            body: BcBody::ModernMsg(ModernMsg {
+                ..Default::default()
+            }),
+        };
+
+        sub_get.send(get)?;
+        let msg = sub_get.rx.recv_timeout(RX_TIMEOUT)?;
+
+        if let BcBody::ModernMsg(ModernMsg {
+            payload:
+                Some(BcPayloads::BcXml(BcXml {
+                    hdd_info_list: Some(hdd_info_list),
+                    ..
+                })),
+            ..
+        }) = msg.body
+        {
+            Ok(hdd_info_list)
+        } else {
+            Err(Error::UnintelligibleReply {
+                reply: msg,
+                why: "Expected HddInfoList xml but it was not recieved",
+            })
+        }
+    }
+}
@@ -97,7 +97,7 @@ impl BcCamera {
                         password: md5_password,
                         user_ver: 1,
                     }),
-                    login_net: Some(LoginNet::default()),
+                    login_net: Some(login_net(connection.udp_port())),
                     ..Default::default()
                 },
             );
@@ -118,10 +118,10 @@ impl BcCamera {
                     self.logged_in = true;
                     device_info = info;
                 }
-                BcBody::ModernMsg(ModernMsg {
-                    extension: None,
-                    payload: None,
-                }) => return Err(Error::AuthFailed),
+                // The camera replies without the DeviceInfo when the credentials are wrong
+                BcBody::ModernMsg(ModernMsg { payload: None, .. }) => {
+                    return Err(Error::AuthFailed)
+                }
                 _ => {
                     return Err(Error::UnintelligibleReply {
                         reply: modern_reply,
@@ -142,3 +142,26 @@ impl BcCamera {
         Ok(device_info)
     }
 }
+
+// The LoginNet tells the camera which of our ports the udp data arrives on,
+// `None` for tcp connections which send `0`
+fn login_net(udp_port: Option<u16>) -> LoginNet {
+    LoginNet {
+        udp_port: udp_port.unwrap_or(0),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_login_net_udp() {
+    let net = login_net(Some(53500));
+    assert_eq!(net.udp_port, 53500);
+    assert_eq!(net.type_, "LAN");
+}
+
+#[test]
+fn test_login_net_tcp() {
+    let net = login_net(None);
+    assert_eq!(net.udp_port, 0);
+    assert_eq!(net.type_, "LAN");
+}
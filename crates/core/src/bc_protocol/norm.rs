@@ -0,0 +1,108 @@
+use super::{BcCamera, Error, Result, RX_TIMEOUT};
+use crate::bc::{model::*, xml::*};
+
+impl BcCamera {
+    ///
+    /// Get the video standard of the camera
+    ///
+    /// # Returns
+    ///
+    /// returns either an error or the norm, usually `"NTSC"` or `"PAL"`
+    ///
+    pub fn get_norm(&self) -> Result<String> {
+        let connection = self
+            .connection
+            .as_ref()
+            .expect("Must be connected to get the norm");
+        let sub_get_general = connection.subscribe(MSG_ID_GET_GENERAL)?;
+        let get = Bc {
+            meta: BcMeta {
+                msg_id: MSG_ID_GET_GENERAL,
+                channel_id: self.channel_id,
+                msg_num: self.new_message_num(),
+                response_code: 0,
+                stream_type: 0,
+                class: 0x6414,
+            },
+            body: BcBody::ModernMsg(ModernMsg::default()),
+        };
+
+        sub_get_general.send(get)?;
+        let msg = sub_get_general.rx.recv_timeout(RX_TIMEOUT)?;
+
+        if let BcBody::ModernMsg(ModernMsg {
+            payload:
+                Some(BcPayloads::BcXml(BcXml {
+                    norm: Some(Norm { norm, .. }),
+                    ..
+                })),
+            ..
+        }) = msg.body
+        {
+            Ok(norm)
+        } else {
+            Err(Error::UnintelligibleReply {
+                reply: msg,
+                why: "Reply did not contain the Norm xml",
+            })
+        }
+    }
+
+    ///
+    /// Set the video standard of the camera
+    ///
+    /// # Parameters
+    ///
+    /// * `norm` - The video standard, either `"NTSC"` or `"PAL"`
+    ///
+    /// # Returns
+    ///
+    /// returns Ok(()) or error
+    ///
+    pub fn set_norm(&self, norm: &str) -> Result<()> {
+        if norm != "NTSC" && norm != "PAL" {
+            return Err(Error::OtherString(format!(
+                "Unknown norm {:?}, it should be NTSC or PAL",
+                norm
+            )));
+        }
+
+        let connection = self
+            .connection
+            .as_ref()
+            .expect("Must be connected to set the norm");
+        let sub_set_general = connection.subscribe(MSG_ID_SET_GENERAL)?;
+        let set = Bc::new_from_xml(
+            BcMeta {
+                msg_id: MSG_ID_SET_GENERAL,
+                channel_id: self.channel_id,
+                msg_num: self.new_message_num(),
+                response_code: 0,
+                stream_type: 0,
+                class: 0x6414,
+            },
+            BcXml {
+                norm: Some(Norm {
+                    version: xml_ver(),
+                    norm: norm.to_string(),
+                }),
+                ..Default::default()
+            },
+        );
+
+        sub_set_general.send(set)?;
+        let msg = sub_set_general.rx.recv_timeout(RX_TIMEOUT)?;
+
+        if let BcMeta {
+            response_code: 200, ..
+        } = msg.meta
+        {
+            Ok(())
+        } else {
+            Err(Error::UnintelligibleReply {
+                reply: msg,
+                why: "The camera did not accept the Norm xml",
+            })
+        }
+    }
+}
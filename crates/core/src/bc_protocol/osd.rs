@@ -0,0 +1,102 @@
+use super::{BcCamera, Error, Result, RX_TIMEOUT};
+use crate::bc::{model::*, xml::*};
+
+impl BcCamera {
+    /// Get the [OsdChannelName] and [OsdDatetime] xml which contain the on screen display
+    /// settings of the camera
+    pub fn get_osd(&self) -> Result<(OsdChannelName, OsdDatetime)> {
+        let connection = self
+            .connection
+            .as_ref()
+            .expect("Must be connected to get the OSD");
+        let sub_get = connection.subscribe(MSG_ID_GET_OSD)?;
+        let get = Bc {
+            meta: BcMeta {
+                msg_id: MSG_ID_GET_OSD,
+                channel_id: self.channel_id,
+                msg_num: self.new_message_num(),
+                response_code: 0,
+                stream_type: 0,
+                class: 0x6414,
+            },
+            body: BcBody::ModernMsg(ModernMsg {
+                extension: Some(Extension {
+                    channel_id: Some(self.channel_id),
+                    ..Default::default()
+                }),
+                payload: None,
+            }),
+        };
+
+        sub_get.send(get)?;
+        let msg = sub_get.rx.recv_timeout(RX_TIMEOUT)?;
+
+        if let BcBody::ModernMsg(ModernMsg {
+            payload:
+                Some(BcPayloads::BcXml(BcXml {
+                    osd_channel_name: Some(osd_channel_name),
+                    osd_datetime: Some(osd_datetime),
+                    ..
+                })),
+            ..
+        }) = msg.body
+        {
+            Ok((osd_channel_name, osd_datetime))
+        } else {
+            Err(Error::UnintelligibleReply {
+                reply: msg,
+                why: "Expected OsdChannelName and OsdDatetime xml but it was not recieved",
+            })
+        }
+    }
+
+    /// Set the on screen display using the [OsdChannelName] and [OsdDatetime] xml
+    pub fn set_osd(
+        &self,
+        osd_channel_name: OsdChannelName,
+        osd_datetime: OsdDatetime,
+    ) -> Result<()> {
+        let connection = self
+            .connection
+            .as_ref()
+            .expect("Must be connected to set the OSD");
+        let sub_set = connection.subscribe(MSG_ID_SET_OSD)?;
+
+        let set = Bc {
+            meta: BcMeta {
+                msg_id: MSG_ID_SET_OSD,
+                channel_id: self.channel_id,
+                msg_num: self.new_message_num(),
+                response_code: 0,
+                stream_type: 0,
+                class: 0x6414,
+            },
+            body: BcBody::ModernMsg(ModernMsg {
+                extension: Some(Extension {
+                    channel_id: Some(self.channel_id),
+                    ..Default::default()
+                }),
+                payload: Some(BcPayloads::BcXml(BcXml {
+                    osd_channel_name: Some(osd_channel_name),
+                    osd_datetime: Some(osd_datetime),
+                    ..Default::default()
+                })),
+            }),
+        };
+
+        sub_set.send(set)?;
+        let msg = sub_set.rx.recv_timeout(RX_TIMEOUT)?;
+
+        if let BcMeta {
+            response_code: 200, ..
+        } = msg.meta
+        {
+            Ok(())
+        } else {
+            Err(Error::UnintelligibleReply {
+                reply: msg,
+                why: "The camera did not accept the OSD xml",
+            })
+        }
+    }
+}
@@ -105,6 +105,24 @@ impl BcCamera {
         self.set_pirstate(pir_state)?;
         Ok(())
     }
+
+    /// This is a convience function to set the PIR sensitivity
+    ///
+    /// The value is the raw `sensiValue` used by the camera
+    pub fn set_pir_sensitivity(&mut self, sensitivity: u8) -> Result<()> {
+        let mut pir_state = self.get_pirstate()?;
+        pir_state.sensiValue = sensitivity;
+        self.set_pirstate(pir_state)?;
+        Ok(())
+    }
+
+    /// This is a convience function to set the times of the week that the PIR is active
+    pub fn set_pir_schedule(&mut self, time_block_list: TimeBlockList) -> Result<()> {
+        let mut pir_state = self.get_pirstate()?;
+        pir_state.timeBlockList = time_block_list;
+        self.set_pirstate(pir_state)?;
+        Ok(())
+    }
 }
 
 /// Turn PIR ON or OFF
@@ -0,0 +1,95 @@
+use super::{BcCamera, Error, Result, RX_TIMEOUT};
+use crate::bc::{model::*, xml::*};
+
+impl BcCamera {
+    /// Get the [Record] xml which contains the recording schedule of the camera
+    pub fn get_record_schedule(&self) -> Result<Record> {
+        let connection = self
+            .connection
+            .as_ref()
+            .expect("Must be connected to get the record schedule");
+        let sub_get = connection.subscribe(MSG_ID_GET_RECORD)?;
+        let get = Bc {
+            meta: BcMeta {
+                msg_id: MSG_ID_GET_RECORD,
+                channel_id: self.channel_id,
+                msg_num: self.new_message_num(),
+                response_code: 0,
+                stream_type: 0,
+                class: 0x6414,
+            },
+            body: BcBody::ModernMsg(ModernMsg {
+                extension: Some(Extension {
+                    channel_id: Some(self.channel_id),
+                    ..Default::default()
+                }),
+                payload: None,
+            }),
+        };
+
+        sub_get.send(get)?;
+        let msg = sub_get.rx.recv_timeout(RX_TIMEOUT)?;
+
+        if let BcBody::ModernMsg(ModernMsg {
+            payload:
+                Some(BcPayloads::BcXml(BcXml {
+                    record: Some(record),
+                    ..
+                })),
+            ..
+        }) = msg.body
+        {
+            Ok(record)
+        } else {
+            Err(Error::UnintelligibleReply {
+                reply: msg,
+                why: "Expected Record xml but it was not recieved",
+            })
+        }
+    }
+
+    /// Set the recording schedule using the [Record] xml
+    pub fn set_record_schedule(&self, record: Record) -> Result<()> {
+        let connection = self
+            .connection
+            .as_ref()
+            .expect("Must be connected to set the record schedule");
+        let sub_set = connection.subscribe(MSG_ID_SET_RECORD)?;
+
+        let set = Bc {
+            meta: BcMeta {
+                msg_id: MSG_ID_SET_RECORD,
+                channel_id: self.channel_id,
+                msg_num: self.new_message_num(),
+                response_code: 0,
+                stream_type: 0,
+                class: 0x6414,
+            },
+            body: BcBody::ModernMsg(ModernMsg {
+                extension: Some(Extension {
+                    channel_id: Some(self.channel_id),
+                    ..Default::default()
+                }),
+                payload: Some(BcPayloads::BcXml(BcXml {
+                    record: Some(record),
+                    ..Default::default()
+                })),
+            }),
+        };
+
+        sub_set.send(set)?;
+        let msg = sub_set.rx.recv_timeout(RX_TIMEOUT)?;
+
+        if let BcMeta {
+            response_code: 200, ..
+        } = msg.meta
+        {
+            Ok(())
+        } else {
+            Err(Error::UnintelligibleReply {
+                reply: msg,
+                why: "The camera did not accept the Record xml",
+            })
+        }
+    }
+}
@@ -14,6 +14,67 @@ pub enum SocketAddrOrUid {
     Uid(String),
 }
 
+/// The ways that a camera can be found from its UID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscoveryMethod {
+    /// Broadcast on the local network for the camera
+    Local,
+    /// Ask the reolink p2p servers for the camera's address
+    Remote,
+}
+
+/// The order that the discovery methods are tried in when none is given
+pub const DEFAULT_DISCOVERY_ORDER: &[DiscoveryMethod] =
+    &[DiscoveryMethod::Local, DiscoveryMethod::Remote];
+
+/// The largest udp packet, including the header, that is sent or
+/// asked for when talking to a camera over udp
+pub const DEFAULT_UDP_MTU: u32 = 1030;
+
+/// The reolink p2p servers that are asked for the camera's address
+/// during [`DiscoveryMethod::Remote`] when none are given
+pub const DEFAULT_P2P_RELAY_HOSTNAMES: &[&str] = &[
+    "p2p.reolink.com",
+    "p2p1.reolink.com",
+    "p2p2.reolink.com",
+    "p2p3.reolink.com",
+    "p2p6.reolink.com",
+    "p2p7.reolink.com",
+    "p2p8.reolink.com",
+    "p2p9.reolink.com",
+    "p2p14.reolink.com",
+    "p2p15.reolink.com",
+];
+
+/// How a camera is found from its UID and then talked to over udp
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UdpOptions {
+    /// The [`DiscoveryMethod`]s to try in order
+    pub discovery: Vec<DiscoveryMethod>,
+    /// The p2p servers to ask during [`DiscoveryMethod::Remote`]
+    pub relay_hostnames: Vec<String>,
+    /// The largest udp packet to send or ask the camera for, lower it on networks
+    /// such as VPNs that drop larger packets
+    pub mtu: u32,
+    /// The local ip to send from and register with the p2p servers, `None` to
+    /// use all interfaces
+    pub source_ip: Option<IpAddr>,
+}
+
+impl Default for UdpOptions {
+    fn default() -> Self {
+        Self {
+            discovery: DEFAULT_DISCOVERY_ORDER.to_vec(),
+            relay_hostnames: DEFAULT_P2P_RELAY_HOSTNAMES
+                .iter()
+                .map(|host| host.to_string())
+                .collect(),
+            mtu: DEFAULT_UDP_MTU,
+            source_ip: None,
+        }
+    }
+}
+
 /// An extension of ToSocketAddrs that will also resolve to a camera UID
 pub trait ToSocketAddrsOrUid: ToSocketAddrs {
     /// The return type of the function
@@ -0,0 +1,46 @@
+use super::{BcCamera, Error, Result, RX_TIMEOUT};
+use crate::bc::{model::*, xml::*};
+
+impl BcCamera {
+    /// Request the [Support] xml which describes the capabilities of the camera
+    pub fn get_support(&self) -> Result<Support> {
+        let connection = self
+            .connection
+            .as_ref()
+            .expect("Must be connected to get the camera capabilities");
+        let sub_get = connection.subscribe(MSG_ID_SUPPORT)?;
+        let get = Bc {
+            meta: BcMeta {
+                msg_id: MSG_ID_SUPPORT,
+                channel_id: self.channel_id,
+                msg_num: self.new_message_num(),
+                response_code: 0,
+                stream_type: 0,
+                class: 0x6414,
+            },
+            body: BcBody::ModernMsg(ModernMsg {
+                ..Default::default()
+            }),
+        };
+
+        sub_get.send(get)?;
+        let msg = sub_get.rx.recv_timeout(RX_TIMEOUT)?;
+
+        if let BcBody::ModernMsg(ModernMsg {
+            payload:
+                Some(BcPayloads::BcXml(BcXml {
+                    support: Some(support),
+                    ..
+                })),
+            ..
+        }) = msg.body
+        {
+            Ok(support)
+        } else {
+            Err(Error::UnintelligibleReply {
+                reply: msg,
+                why: "Expected Support xml but it was not recieved",
+            })
+        }
+    }
+}
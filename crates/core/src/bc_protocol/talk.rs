@@ -1,10 +1,60 @@
-use super::{BcCamera, Error, Result, RX_TIMEOUT};
+use super::{BcCamera, BcSubscription, Error, Result, TalkRejection, RX_TIMEOUT};
 use crate::{bc::model::*, bc::xml::*, bcmedia::model::*};
 use std::io::{BufRead, Error as IoError, ErrorKind, Read};
 use std::sync::mpsc::Receiver;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
 
 type IoResult<T> = std::result::Result<T, IoError>;
 
+/// How long a talk will wait for an earlier talk on the same camera to finish
+const TALK_QUEUE_TIMEOUT: Duration = Duration::from_secs(60);
+
+// The talk keys of the cameras that are talking in this process
+//
+// This is process wide rather than per BcCamera so that talks made through separate
+// connections to the same camera still queue. Talks from other processes are not seen
+// here, the camera answers those with a 422 busy
+static ACTIVE_TALKS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+// Notified whenever a talk finishes
+static TALK_FINISHED: Condvar = Condvar::new();
+
+// Held for the whole of a talk, the next talk to the camera may start once it is dropped
+struct TalkGuard {
+    key: String,
+}
+
+impl Drop for TalkGuard {
+    fn drop(&mut self) {
+        // A talk that panicked has still finished with the channel
+        let mut active = ACTIVE_TALKS.lock().unwrap_or_else(|e| e.into_inner());
+        active.retain(|key| key != &self.key);
+        TALK_FINISHED.notify_all();
+    }
+}
+
+// Waits up to `timeout` for any other talk with the same key to finish
+fn lock_talk(key: &str, timeout: Duration) -> Result<TalkGuard> {
+    let deadline = Instant::now() + timeout;
+    let mut active = ACTIVE_TALKS.lock().unwrap_or_else(|e| e.into_inner());
+    while active.iter().any(|active_key| active_key == key) {
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(Error::Other(
+                "Timed out waiting for another talk to the camera to finish",
+            ));
+        }
+        active = TALK_FINISHED
+            .wait_timeout(active, deadline - now)
+            .unwrap_or_else(|e| e.into_inner())
+            .0;
+    }
+    active.push(key.to_string());
+    Ok(TalkGuard {
+        key: key.to_string(),
+    })
+}
+
 impl BcCamera {
     ///
     /// Finish Talk
@@ -110,6 +160,9 @@ impl BcCamera {
     ///
     /// It also checks that it is ADPCM as the code is written to accept only that
     ///
+    /// If another talk to this camera is in progress in this process this waits for it
+    /// to finish first, for up to a minute
+    ///
     /// # Parameters
     ///
     /// * `adpcm` - Data must be adpcm in DVI-4 format
@@ -119,6 +172,7 @@ impl BcCamera {
     ///
     pub fn talk(&self, adpcm: &[u8], talk_config: TalkConfig) -> Result<()> {
         let connection = self.connection.as_ref().expect("Must be connected");
+        let _talk_guard = lock_talk(&self.talk_key, TALK_QUEUE_TIMEOUT)?;
 
         let sub = connection.subscribe(MSG_ID_TALKCONFIG)?;
 
@@ -168,74 +222,75 @@ impl BcCamera {
             msg = sub.rx.recv_timeout(RX_TIMEOUT)?;
         }
 
-        if let BcMeta {
-            response_code: 200, ..
-        } = msg.meta
-        {
-        } else {
-            return Err(Error::UnintelligibleReply {
-                reply: msg,
-                why:
-                    "The camera did not accept the TalkConfig xml. Audio format is likely incorrect",
-            });
+        if msg.meta.response_code != 200 {
+            return Err(Error::TalkRejected(TalkRejection(msg.meta.response_code)));
         }
 
-        let full_block_size = block_size + 4; // Block size + predictor state
-        let sub = connection.subscribe(MSG_ID_TALK)?;
-
-        const BLOCK_PER_PAYLOAD: usize = 4;
-        const BLOCK_HEADER_SIZE: usize = 4;
-        const SAMPLES_PER_BYTE: usize = 2;
-
-        for payload_bytes in adpcm.chunks(full_block_size as usize * BLOCK_PER_PAYLOAD) {
-            let mut payload = vec![];
-            for bytes in payload_bytes.chunks(full_block_size as usize) {
-                let bcmedia_adpcm = BcMedia::Adpcm(BcMediaAdpcm {
-                    data: bytes.to_vec(),
-                });
-                payload = bcmedia_adpcm.serialize(payload)?;
-            }
+        // The camera has opened a talk session, so it is stopped however the talk ends.
+        // Otherwise the camera stays busy for the next talk
+        let result = (|| -> Result<()> {
+            let full_block_size = block_size + 4; // Block size + predictor state
+            let sub = connection.subscribe(MSG_ID_TALK)?;
+
+            const BLOCK_PER_PAYLOAD: usize = 4;
+            const BLOCK_HEADER_SIZE: usize = 4;
+            const SAMPLES_PER_BYTE: usize = 2;
+
+            for payload_bytes in adpcm.chunks(full_block_size as usize * BLOCK_PER_PAYLOAD) {
+                let mut payload = vec![];
+                for bytes in payload_bytes.chunks(full_block_size as usize) {
+                    let bcmedia_adpcm = BcMedia::Adpcm(BcMediaAdpcm {
+                        data: bytes.to_vec(),
+                    });
+                    payload = bcmedia_adpcm.serialize(payload)?;
+                }
 
-            let msg = Bc {
-                meta: BcMeta {
-                    msg_id: MSG_ID_TALK,
-                    channel_id: self.channel_id,
-                    msg_num: self.new_message_num(),
-                    stream_type: 0,
-                    response_code: 0,
-                    class: 0x6414,
-                },
-                body: BcBody::ModernMsg(ModernMsg {
-                    extension: Some(Extension {
-                        channel_id: Some(self.channel_id),
-                        binary_data: Some(1),
-                        ..Default::default()
+                let msg = Bc {
+                    meta: BcMeta {
+                        msg_id: MSG_ID_TALK,
+                        channel_id: self.channel_id,
+                        msg_num: self.new_message_num(),
+                        stream_type: 0,
+                        response_code: 0,
+                        class: 0x6414,
+                    },
+                    body: BcBody::ModernMsg(ModernMsg {
+                        extension: Some(Extension {
+                            channel_id: Some(self.channel_id),
+                            binary_data: Some(1),
+                            ..Default::default()
+                        }),
+                        payload: Some(BcPayloads::Binary(payload)),
                     }),
-                    payload: Some(BcPayloads::Binary(payload)),
-                }),
-            };
-
-            sub.send(msg)?;
+                };
+
+                sub.send(msg)?;
+                check_talk_replies(&sub)?;
+
+                let adpcm_len = payload_bytes.len();
+                // There are two samples per byte
+                //
+                // To calculate the bytes we subtract the block headers from the len
+                //
+                // There is 1 initial sample stored in the block header so we add that in the end
+                //
+                let samples_sent = (adpcm_len - BLOCK_HEADER_SIZE * BLOCK_PER_PAYLOAD)
+                    * SAMPLES_PER_BYTE
+                    + BLOCK_PER_PAYLOAD;
+
+                // Time to play the sample in seconds
+                let play_length = samples_sent as f32 / sample_rate as f32;
+                std::thread::sleep(std::time::Duration::from_secs_f32(play_length));
+            }
 
-            let adpcm_len = payload_bytes.len();
-            // There are two samples per byte
-            //
-            // To calculate the bytes we subtract the block headers from the len
-            //
-            // There is 1 initial sample stored in the block header so we add that in the end
-            //
-            let samples_sent = (adpcm_len - BLOCK_HEADER_SIZE * BLOCK_PER_PAYLOAD)
-                * SAMPLES_PER_BYTE
-                + BLOCK_PER_PAYLOAD;
-
-            // Time to play the sample in seconds
-            let play_length = samples_sent as f32 / sample_rate as f32;
-            std::thread::sleep(std::time::Duration::from_secs_f32(play_length));
+            Ok(())
+        })();
+        if result.is_err() {
+            let _ = self.talk_stop();
+            return result;
         }
 
-        self.talk_stop()?;
-
-        Ok(())
+        self.talk_stop()
     }
 
     ///
@@ -249,6 +304,9 @@ impl BcCamera {
     ///
     /// It also checks that it is ADPCM as the code is written to accept only that
     ///
+    /// If another talk to this camera is in progress in this process this waits for it
+    /// to finish first, for up to a minute
+    ///
     /// # Parameters
     ///
     /// * `adpcm` - Data must be adpcm in DVI-4 format
@@ -258,6 +316,7 @@ impl BcCamera {
     ///
     pub fn talk_stream(&self, rx: Receiver<Vec<u8>>, talk_config: TalkConfig) -> Result<()> {
         let connection = self.connection.as_ref().expect("Must be connected");
+        let _talk_guard = lock_talk(&self.talk_key, TALK_QUEUE_TIMEOUT)?;
 
         let sub = connection.subscribe(MSG_ID_TALKCONFIG)?;
 
@@ -307,102 +366,115 @@ impl BcCamera {
             msg = sub.rx.recv_timeout(RX_TIMEOUT)?;
         }
 
-        if let BcMeta {
-            response_code: 200, ..
-        } = msg.meta
-        {
-        } else {
-            return Err(Error::UnintelligibleReply {
-                reply: msg,
-                why:
-                    "The camera did not accept the TalkConfig xml. Audio format is likely incorrect",
-            });
+        if msg.meta.response_code != 200 {
+            return Err(Error::TalkRejected(TalkRejection(msg.meta.response_code)));
         }
 
-        let full_block_size = block_size + 4; // Block size + predictor state
-        let sub = connection.subscribe(MSG_ID_TALK)?;
-
-        const BLOCK_PER_PAYLOAD: usize = 1;
-        const BLOCK_HEADER_SIZE: usize = 4;
-        const SAMPLES_PER_BYTE: usize = 2;
-
-        let mut buffered_recv = BufferedStream::from_rx(rx);
+        // The camera has opened a talk session, so it is stopped however the talk ends.
+        // Otherwise the camera stays busy for the next talk
+        let result = (|| -> Result<()> {
+            let full_block_size = block_size + 4; // Block size + predictor state
+            let sub = connection.subscribe(MSG_ID_TALK)?;
+
+            const BLOCK_PER_PAYLOAD: usize = 1;
+            const BLOCK_HEADER_SIZE: usize = 4;
+            const SAMPLES_PER_BYTE: usize = 2;
+
+            let mut buffered_recv = BufferedStream::from_rx(rx);
+
+            let target_chunks = full_block_size as usize * BLOCK_PER_PAYLOAD;
+
+            let mut payload_bytes = vec![];
+            let mut end_of_stream = false;
+            while !end_of_stream {
+                while payload_bytes.len() < target_chunks {
+                    let mut buffer = vec![255; target_chunks - payload_bytes.len()];
+                    if let Ok(read) = buffered_recv.read(&mut buffer) {
+                        payload_bytes.extend(&buffer[..read]);
+                    } else {
+                        // Error should occur if the channel is dropped
+                        // and all bytes are consumed
+                        end_of_stream = true;
+                    }
+                    if end_of_stream {
+                        break;
+                    }
+                }
 
-        let target_chunks = full_block_size as usize * BLOCK_PER_PAYLOAD;
+                let mut payload = vec![];
+                for block_bytes in payload_bytes.chunks(full_block_size as usize) {
+                    let bytes: Vec<u8> = block_bytes.to_vec();
+                    let bcmedia_adpcm = BcMedia::Adpcm(BcMediaAdpcm { data: bytes });
+                    payload = bcmedia_adpcm.serialize(payload)?;
+                }
 
-        let mut payload_bytes = vec![];
-        let mut end_of_stream = false;
-        while !end_of_stream {
-            while payload_bytes.len() < target_chunks {
-                let mut buffer = vec![255; target_chunks - payload_bytes.len()];
-                if let Ok(read) = buffered_recv.read(&mut buffer) {
-                    payload_bytes.extend(&buffer[..read]);
+                let adpcm_len = payload_bytes.len();
+
+                // There are two samples per byte
+                //
+                // To calculate the bytes we subtract the block headers from the len
+                //
+                // There is 1 initial sample stored in the block header so we add that in the end
+                //
+                let samples_sent = if adpcm_len >= BLOCK_HEADER_SIZE * BLOCK_PER_PAYLOAD {
+                    (adpcm_len - BLOCK_HEADER_SIZE * BLOCK_PER_PAYLOAD) * SAMPLES_PER_BYTE
+                        + BLOCK_PER_PAYLOAD
                 } else {
-                    // Error should occur if the channel is dropped
-                    // and all bytes are consumed
-                    end_of_stream = true;
-                }
-                if end_of_stream {
+                    // Zero samples in this block
                     break;
-                }
-            }
-
-            let mut payload = vec![];
-            for block_bytes in payload_bytes.chunks(full_block_size as usize) {
-                let bytes: Vec<u8> = block_bytes.to_vec();
-                let bcmedia_adpcm = BcMedia::Adpcm(BcMediaAdpcm { data: bytes });
-                payload = bcmedia_adpcm.serialize(payload)?;
-            }
-
-            let adpcm_len = payload_bytes.len();
-
-            // There are two samples per byte
-            //
-            // To calculate the bytes we subtract the block headers from the len
-            //
-            // There is 1 initial sample stored in the block header so we add that in the end
-            //
-            let samples_sent = if adpcm_len >= BLOCK_HEADER_SIZE * BLOCK_PER_PAYLOAD {
-                (adpcm_len - BLOCK_HEADER_SIZE * BLOCK_PER_PAYLOAD) * SAMPLES_PER_BYTE
-                    + BLOCK_PER_PAYLOAD
-            } else {
-                // Zero samples in this block
-                break;
-            };
-
-            payload_bytes = vec![];
-
-            // Time to play the sample in seconds
-            let play_length = samples_sent as f32 / sample_rate as f32;
-
-            let msg = Bc {
-                meta: BcMeta {
-                    msg_id: MSG_ID_TALK,
-                    channel_id: self.channel_id,
-                    msg_num: self.new_message_num(),
-                    stream_type: 0,
-                    response_code: 0,
-                    class: 0x6414,
-                },
-                body: BcBody::ModernMsg(ModernMsg {
-                    extension: Some(Extension {
-                        channel_id: Some(self.channel_id),
-                        binary_data: Some(1),
-                        ..Default::default()
+                };
+
+                payload_bytes = vec![];
+
+                // Time to play the sample in seconds
+                let play_length = samples_sent as f32 / sample_rate as f32;
+
+                let msg = Bc {
+                    meta: BcMeta {
+                        msg_id: MSG_ID_TALK,
+                        channel_id: self.channel_id,
+                        msg_num: self.new_message_num(),
+                        stream_type: 0,
+                        response_code: 0,
+                        class: 0x6414,
+                    },
+                    body: BcBody::ModernMsg(ModernMsg {
+                        extension: Some(Extension {
+                            channel_id: Some(self.channel_id),
+                            binary_data: Some(1),
+                            ..Default::default()
+                        }),
+                        payload: Some(BcPayloads::Binary(payload)),
                     }),
-                    payload: Some(BcPayloads::Binary(payload)),
-                }),
-            };
+                };
 
-            sub.send(msg)?;
+                sub.send(msg)?;
+                check_talk_replies(&sub)?;
+
+                std::thread::sleep(std::time::Duration::from_secs_f32(play_length * 0.95));
+            }
 
-            std::thread::sleep(std::time::Duration::from_secs_f32(play_length * 0.95));
+            Ok(())
+        })();
+        if result.is_err() {
+            let _ = self.talk_stop();
+            return result;
         }
 
-        self.talk_stop()?;
+        self.talk_stop()
+    }
+}
 
-        Ok(())
+// The camera does not reply to each block of audio but if it gives up on the talk
+// it replies with an error code. Check for that without waiting so the talk stops
+// with a clear error rather than playing into the void
+fn check_talk_replies(sub: &BcSubscription) -> Result<()> {
+    while let Ok(reply) = sub.rx.try_recv() {
+        if reply.meta.response_code != 200 {
+            return Err(Error::TalkRejected(TalkRejection(reply.meta.response_code)));
+        }
     }
+    Ok(())
 }
 
 struct BufferedStream {
@@ -466,3 +538,37 @@ impl BufRead for BufferedStream {
         self.consumed += amt;
     }
 }
+
+#[test]
+fn test_talk_queue() {
+    let key = "test_talk_queue/0";
+    let first = lock_talk(key, TALK_QUEUE_TIMEOUT).unwrap();
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let second = std::thread::spawn(move || {
+        let _guard = lock_talk(key, TALK_QUEUE_TIMEOUT).unwrap();
+        tx.send(Instant::now()).unwrap();
+    });
+
+    // The second talk must wait for the first to finish
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(rx.try_recv().is_err());
+
+    let released = Instant::now();
+    drop(first);
+    let acquired = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(acquired >= released);
+    second.join().unwrap();
+}
+
+#[test]
+fn test_talk_queue_timeout() {
+    let key = "test_talk_queue_timeout/0";
+    let _first = lock_talk(key, TALK_QUEUE_TIMEOUT).unwrap();
+
+    let second = std::thread::spawn(move || lock_talk(key, Duration::from_millis(100)).is_err());
+    assert!(second.join().unwrap());
+
+    // Talks to other cameras do not wait
+    assert!(lock_talk("test_talk_queue_timeout/1", Duration::from_millis(100)).is_ok());
+}
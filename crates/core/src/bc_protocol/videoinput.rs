@@ -0,0 +1,104 @@
+use super::{BcCamera, Error, Result, RX_TIMEOUT};
+use crate::bc::{model::*, xml::*};
+
+impl BcCamera {
+    /// Get the [VideoInput] xml which contains the image settings of the camera along
+    /// with the [InputAdvanceCfg] xml if the camera sent it
+    pub fn get_image_settings(&self) -> Result<(VideoInput, Option<InputAdvanceCfg>)> {
+        let connection = self
+            .connection
+            .as_ref()
+            .expect("Must be connected to get image settings");
+        let sub_get = connection.subscribe(MSG_ID_GET_VIDEO_INPUT)?;
+        let get = Bc {
+            meta: BcMeta {
+                msg_id: MSG_ID_GET_VIDEO_INPUT,
+                channel_id: self.channel_id,
+                msg_num: self.new_message_num(),
+                response_code: 0,
+                stream_type: 0,
+                class: 0x6414,
+            },
+            body: BcBody::ModernMsg(ModernMsg {
+                extension: Some(Extension {
+                    channel_id: Some(self.channel_id),
+                    ..Default::default()
+                }),
+                payload: None,
+            }),
+        };
+
+        sub_get.send(get)?;
+        let msg = sub_get.rx.recv_timeout(RX_TIMEOUT)?;
+
+        if let BcBody::ModernMsg(ModernMsg {
+            payload:
+                Some(BcPayloads::BcXml(BcXml {
+                    video_input: Some(video_input),
+                    input_advance_cfg,
+                    ..
+                })),
+            ..
+        }) = msg.body
+        {
+            Ok((video_input, input_advance_cfg))
+        } else {
+            Err(Error::UnintelligibleReply {
+                reply: msg,
+                why: "Expected VideoInput xml but it was not recieved",
+            })
+        }
+    }
+
+    /// Set the image settings using the [VideoInput] xml
+    ///
+    /// The [InputAdvanceCfg] xml is only sent if given
+    pub fn set_image_settings(
+        &self,
+        video_input: VideoInput,
+        input_advance_cfg: Option<InputAdvanceCfg>,
+    ) -> Result<()> {
+        let connection = self
+            .connection
+            .as_ref()
+            .expect("Must be connected to set image settings");
+        let sub_set = connection.subscribe(MSG_ID_SET_VIDEO_INPUT)?;
+
+        let set = Bc {
+            meta: BcMeta {
+                msg_id: MSG_ID_SET_VIDEO_INPUT,
+                channel_id: self.channel_id,
+                msg_num: self.new_message_num(),
+                response_code: 0,
+                stream_type: 0,
+                class: 0x6414,
+            },
+            body: BcBody::ModernMsg(ModernMsg {
+                extension: Some(Extension {
+                    channel_id: Some(self.channel_id),
+                    ..Default::default()
+                }),
+                payload: Some(BcPayloads::BcXml(BcXml {
+                    video_input: Some(video_input),
+                    input_advance_cfg,
+                    ..Default::default()
+                })),
+            }),
+        };
+
+        sub_set.send(set)?;
+        let msg = sub_set.rx.recv_timeout(RX_TIMEOUT)?;
+
+        if let BcMeta {
+            response_code: 200, ..
+        } = msg.meta
+        {
+            Ok(())
+        } else {
+            Err(Error::UnintelligibleReply {
+                reply: msg,
+                why: "The camera did not accept the VideoInput xml",
+            })
+        }
+    }
+}
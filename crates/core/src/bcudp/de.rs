@@ -182,7 +182,7 @@ mod tests {
     use assert_matches::assert_matches;
     use env_logger::Env;
     use log::*;
-    use std::io::ErrorKind;
+    use std::io::{BufRead, ErrorKind};
     use std::path::PathBuf;
 
     fn init() {
@@ -196,6 +196,29 @@ mod tests {
         dir.join("src").join("bcudp").join("samples").join(name)
     }
 
+    // Replays captured udp packets as one continuous stream and returns every packet
+    // that was read, failing with the index of the first packet that does not parse
+    //
+    // To add a capture from a bug report save the payload of each udp packet into
+    // `samples` and replay them in the order they were captured
+    fn replay(files: Vec<PathBuf>) -> Vec<BcUdp> {
+        let mut subscriber = FileSubscriber::from_files(files);
+
+        let mut packets = vec![];
+        // Stop at the end of the capture rather than waiting for more data
+        while !subscriber.fill_buf().unwrap().is_empty() {
+            match BcUdp::deserialize(&mut subscriber) {
+                Ok(packet) => packets.push(packet),
+                Err(e) => panic!(
+                    "Packet {} of the replay failed to parse: {:?}",
+                    packets.len(),
+                    e
+                ),
+            }
+        }
+        packets
+    }
+
     #[test]
     // Tests the decoding of a UdpDiscovery with a discovery xml
     fn test_nego_disconnect() {
@@ -366,4 +389,76 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    // The negotiation replayed in the order that it happens
+    fn test_replay_negotiate() {
+        init();
+
+        let packets = replay(vec![
+            sample("udp_negotiate_clientt.bin"),
+            sample("udp_negotiate_camt.bin"),
+            sample("udp_negotiate_camcfm.bin"),
+            sample("udp_negotiate_disc.bin"),
+        ]);
+
+        assert_eq!(packets.len(), 4);
+        assert_matches!(
+            packets[0],
+            BcUdp::Discovery(UdpDiscovery {
+                payload: UdpXml { c2d_t: Some(_), .. },
+                ..
+            })
+        );
+        assert_matches!(
+            packets[1],
+            BcUdp::Discovery(UdpDiscovery {
+                payload: UdpXml { d2c_t: Some(_), .. },
+                ..
+            })
+        );
+        assert_matches!(
+            packets[2],
+            BcUdp::Discovery(UdpDiscovery {
+                payload: UdpXml {
+                    d2c_cfm: Some(_),
+                    ..
+                },
+                ..
+            })
+        );
+        assert_matches!(
+            packets[3],
+            BcUdp::Discovery(UdpDiscovery {
+                payload: UdpXml {
+                    c2d_disc: Some(_),
+                    ..
+                },
+                ..
+            })
+        );
+    }
+
+    #[test]
+    // A run of data packets replayed through the same reader
+    fn test_replay_multi() {
+        init();
+
+        let packets = replay(
+            (0..10)
+                .map(|i| sample(&format!("udp_multi_{}.bin", i)))
+                .collect(),
+        );
+
+        assert_eq!(packets.len(), 10);
+        let discovery = packets
+            .iter()
+            .filter(|packet| matches!(packet, BcUdp::Discovery(_)))
+            .count();
+        let data = packets
+            .iter()
+            .filter(|packet| matches!(packet, BcUdp::Data(_)))
+            .count();
+        assert_eq!((discovery, data), (7, 3));
+    }
 }
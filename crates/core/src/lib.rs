@@ -38,4 +38,7 @@ pub mod bcudp;
 /// Most commands will either return their `Ok(result)` or this `Err(Error)`
 pub use bc_protocol::Error;
 
+/// The version of this crate, useful when reporting issues
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 pub(crate) const RX_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
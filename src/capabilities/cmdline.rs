@@ -0,0 +1,8 @@
+use structopt::StructOpt;
+
+/// The capabilities command will print what the camera supports
+#[derive(StructOpt, Debug)]
+pub struct Opt {
+    /// The name of the camera to query. Must be a name in the config
+    pub camera: String,
+}
@@ -0,0 +1,38 @@
+///
+/// # Neolink Capabilities
+///
+/// This module handles the capabilities subcommand
+///
+/// The subcommand prints the capabilities the camera reports
+/// in its Support xml as JSON. Such as whether it has PTZ, talk back,
+/// LED control etc.
+///
+/// # Usage
+///
+/// ```bash
+/// neolink capabilities --config=config.toml CameraName
+/// ```
+///
+use anyhow::{Context, Result};
+
+mod cmdline;
+
+use super::config::Config;
+use crate::utils::find_and_connect;
+pub(crate) use cmdline::Opt;
+
+/// Entry point for the capabilities subcommand
+///
+/// Opt is the command line options
+pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
+    let camera = find_and_connect(&config, &opt.camera)?;
+
+    let support = camera
+        .get_support()
+        .context("Could not get the capabilities of the camera")?;
+    let json =
+        serde_json::to_string_pretty(&support).context("Could not convert the capabilities")?;
+    println!("{}", json);
+
+    Ok(())
+}
@@ -14,6 +14,22 @@ use structopt::{clap::AppSettings, StructOpt};
 pub struct Opt {
     #[structopt(short, long, global(true), parse(from_os_str))]
     pub config: Option<PathBuf>,
+    /// The format of the log output
+    #[structopt(
+        long,
+        global(true),
+        default_value = "text",
+        possible_values(&["text", "json"])
+    )]
+    pub log_format: String,
+    /// Write the raw packets sent to and received from the cameras into this
+    /// directory for debugging
+    #[structopt(long, global(true), parse(from_os_str))]
+    pub dump_packets: Option<PathBuf>,
+    /// Use this channel instead of the channel_id in the config. Useful to
+    /// check the other channels of an NVR without adding them to the config
+    #[structopt(long, global(true))]
+    pub channel: Option<u8>,
     #[structopt(subcommand)]
     pub cmd: Option<Command>,
 }
@@ -25,4 +41,16 @@ pub enum Command {
     Reboot(super::reboot::Opt),
     Pir(super::pir::Opt),
     Talk(super::talk::Opt),
+    ImageSettings(super::imagesettings::Opt),
+    Capabilities(super::capabilities::Opt),
+    Osd(super::osd::Opt),
+    Profile(super::profile::Opt),
+    Norm(super::norm::Opt),
+    Rtmp(super::rtmp::Opt),
+    IrLight(super::irlight::Opt),
+    TestConfig(super::testconfig::Opt),
+    SetName(super::setname::Opt),
+    VersionCheck(super::versioncheck::Opt),
+    DumpConfig(super::dumpconfig::Opt),
+    RecordSchedule(super::recordschedule::Opt),
 }
@@ -1,7 +1,10 @@
 use lazy_static::lazy_static;
+use neolink_core::bc_protocol::{DEFAULT_P2P_RELAY_HOSTNAMES, DEFAULT_UDP_MTU};
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::clone::Clone;
+use std::net::IpAddr;
+use std::path::PathBuf;
 use std::time::Duration;
 use validator::{Validate, ValidationError};
 use validator_derive::Validate;
@@ -9,10 +12,14 @@ use validator_derive::Validate;
 lazy_static! {
     static ref RE_STREAM_SRC: Regex =
         Regex::new(r"^(mainStream|subStream|externStream|both|all)$").unwrap();
+    static ref RE_DISCOVERY_METHOD: Regex = Regex::new(r"^(local|remote)$").unwrap();
+    static ref RE_TRANSCODE: Regex = Regex::new(r"^(h264)$").unwrap();
+    static ref RE_AUDIO: Regex = Regex::new(r"^(pcm|aac|none)$").unwrap();
     static ref RE_TLS_CLIENT_AUTH: Regex = Regex::new(r"^(none|request|require)$").unwrap();
 }
 
-#[derive(Debug, Deserialize, Validate, Clone)]
+#[derive(Debug, Deserialize, Serialize, Validate, Clone)]
+#[validate(schema(function = "validate_config"))]
 pub(crate) struct Config {
     #[validate]
     pub(crate) cameras: Vec<CameraConfig>,
@@ -38,9 +45,15 @@ pub(crate) struct Config {
     #[validate]
     #[serde(default)]
     pub(crate) users: Vec<UserConfig>,
+
+    pub(crate) max_rtsp_clients: Option<usize>,
+
+    #[validate]
+    #[serde(default)]
+    pub(crate) profiles: Vec<ProfileConfig>,
 }
 
-#[derive(Debug, Deserialize, Validate, Clone)]
+#[derive(Debug, Deserialize, Serialize, Validate, Clone)]
 #[validate(schema(function = "validate_camera_config"))]
 pub(crate) struct CameraConfig {
     pub(crate) name: String,
@@ -51,6 +64,27 @@ pub(crate) struct CameraConfig {
     #[serde(rename = "uid")]
     pub(crate) camera_uid: Option<String>,
 
+    #[serde(default = "default_rediscover_on_failure")]
+    pub(crate) rediscover_on_failure: bool,
+
+    #[serde(default = "default_discovery_order")]
+    pub(crate) discovery_order: Vec<String>,
+
+    #[serde(default = "default_p2p_relay_servers")]
+    pub(crate) p2p_relay_servers: Vec<String>,
+
+    #[validate(range(min = 128, max = 1500, message = "Invalid udp mtu", code = "udp_mtu"))]
+    #[serde(default = "default_udp_mtu")]
+    pub(crate) udp_mtu: u32,
+
+    // When set the uid is only used to find the camera's ip, the camera is then
+    // connected to over tcp on this port
+    pub(crate) uid_tcp_port: Option<u16>,
+
+    // The local ip that udp connections are made from, on hosts with several
+    // interfaces this is also the ip registered with the p2p servers
+    pub(crate) source_ip: Option<IpAddr>,
+
     pub(crate) username: String,
     pub(crate) password: Option<String>,
 
@@ -68,14 +102,47 @@ pub(crate) struct CameraConfig {
     #[serde(default = "default_stream")]
     pub(crate) stream: String,
 
+    #[validate(regex(
+        path = "RE_TRANSCODE",
+        message = "Incorrect transcode codec",
+        code = "transcode"
+    ))]
+    pub(crate) transcode: Option<String>,
+
+    #[validate(regex(path = "RE_AUDIO", message = "Incorrect audio codec", code = "audio"))]
+    #[serde(default = "default_audio")]
+    pub(crate) audio: String,
+
     pub(crate) permitted_users: Option<Vec<String>>,
 
+    pub(crate) profile: Option<String>,
+
+    #[serde(default)]
+    pub(crate) on_demand_only: bool,
+
+    #[validate(range(
+        max = 10000,
+        message = "Invalid rtsp latency",
+        code = "rtsp_latency_ms"
+    ))]
+    #[serde(default = "default_rtsp_latency_ms")]
+    pub(crate) rtsp_latency_ms: u32,
+
+    // Extra gstreamer elements spliced into the video pipeline of the rtsp server,
+    // these are checked when the rtsp server starts
+    pub(crate) pipeline_prepend: Option<String>,
+    pub(crate) pipeline_append: Option<String>,
+
     #[validate(range(min = 0, max = 31, message = "Invalid channel", code = "channel_id"))]
     #[serde(default = "default_channel_id")]
     pub(crate) channel_id: u8,
+
+    // Set from the --dump-packets command line option
+    #[serde(skip)]
+    pub(crate) dump_packets: Option<PathBuf>,
 }
 
-#[derive(Debug, Deserialize, Validate, Clone)]
+#[derive(Debug, Deserialize, Serialize, Validate, Clone)]
 pub(crate) struct UserConfig {
     #[validate(custom = "validate_username")]
     #[serde(alias = "username")]
@@ -85,6 +152,26 @@ pub(crate) struct UserConfig {
     pub(crate) pass: String,
 }
 
+#[derive(Debug, Deserialize, Serialize, Validate, Clone)]
+pub(crate) struct ProfileConfig {
+    pub(crate) name: String,
+
+    #[validate]
+    pub(crate) main_stream: Option<StreamProfileConfig>,
+
+    #[validate]
+    pub(crate) sub_stream: Option<StreamProfileConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, Clone)]
+#[validate(schema(function = "validate_stream_profile_config"))]
+pub(crate) struct StreamProfileConfig {
+    pub(crate) width: Option<u32>,
+    pub(crate) height: Option<u32>,
+    pub(crate) bitrate: Option<u32>,
+    pub(crate) fps: Option<u32>,
+}
+
 fn default_bind_addr() -> String {
     "0.0.0.0".to_string()
 }
@@ -97,6 +184,10 @@ fn default_stream() -> String {
     "both".to_string()
 }
 
+fn default_audio() -> String {
+    "pcm".to_string()
+}
+
 fn default_certificate() -> Option<String> {
     None
 }
@@ -109,6 +200,30 @@ fn default_channel_id() -> u8 {
     0
 }
 
+fn default_rediscover_on_failure() -> bool {
+    false
+}
+
+fn default_discovery_order() -> Vec<String> {
+    vec!["local".to_string(), "remote".to_string()]
+}
+
+// The default of gst-rtsp-server
+fn default_rtsp_latency_ms() -> u32 {
+    200
+}
+
+fn default_udp_mtu() -> u32 {
+    DEFAULT_UDP_MTU
+}
+
+fn default_p2p_relay_servers() -> Vec<String> {
+    DEFAULT_P2P_RELAY_HOSTNAMES
+        .iter()
+        .map(|host| host.to_string())
+        .collect()
+}
+
 pub(crate) static RESERVED_NAMES: &[&str] = &["anyone", "anonymous"];
 fn validate_username(name: &str) -> Result<(), ValidationError> {
     if name.trim().is_empty() {
@@ -125,8 +240,57 @@ fn validate_camera_config(camera_config: &CameraConfig) -> Result<(), Validation
         (None, None) => Err(ValidationError::new(
             "Either camera address or uid must be given",
         )),
-        (Some(_), Some(_)) => Err(ValidationError::new(
-            "Must provide either camera address or uid not both",
+        (Some(_), Some(_)) if !camera_config.rediscover_on_failure => Err(ValidationError::new(
+            "Must provide either camera address or uid not both, unless rediscover_on_failure is set",
+        )),
+        _ => Ok(()),
+    }?;
+    if camera_config.discovery_order.is_empty() {
+        return Err(ValidationError::new(
+            "discovery_order must contain at least one of local or remote",
+        ));
+    }
+    if !camera_config
+        .discovery_order
+        .iter()
+        .all(|method| RE_DISCOVERY_METHOD.is_match(method))
+    {
+        return Err(ValidationError::new(
+            "discovery_order can only contain local or remote",
+        ));
+    }
+    if camera_config.p2p_relay_servers.is_empty()
+        && camera_config
+            .discovery_order
+            .iter()
+            .any(|method| method == "remote")
+    {
+        return Err(ValidationError::new(
+            "p2p_relay_servers must not be empty when using remote discovery",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_config(config: &Config) -> Result<(), ValidationError> {
+    for camera_config in config.cameras.iter() {
+        if let Some(profile) = &camera_config.profile {
+            if !config.profiles.iter().any(|p| &p.name == profile) {
+                return Err(ValidationError::new(
+                    "Camera profile must be the name of one of the profiles",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_stream_profile_config(
+    stream_profile: &StreamProfileConfig,
+) -> Result<(), ValidationError> {
+    match (stream_profile.width, stream_profile.height) {
+        (Some(_), None) | (None, Some(_)) => Err(ValidationError::new(
+            "Profile width and height must be given together",
         )),
         _ => Ok(()),
     }
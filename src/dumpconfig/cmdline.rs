@@ -0,0 +1,9 @@
+use structopt::StructOpt;
+
+/// The dump-config command will print the config with all the default values filled in
+#[derive(StructOpt, Debug)]
+pub struct Opt {
+    /// Print the passwords instead of hiding them
+    #[structopt(long)]
+    pub show_passwords: bool,
+}
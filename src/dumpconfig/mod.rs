@@ -0,0 +1,49 @@
+///
+/// # Neolink Dump Config
+///
+/// This module handles the dump-config subcommand
+///
+/// The subcommand prints the config after it has been loaded and
+/// validated, with every default value written out. This shows what
+/// neolink will actually use for the options that were left out of
+/// the config file.
+///
+/// Passwords are replaced with `"********"` unless `--show-passwords`
+/// is given so that the output can be shared in bug reports.
+///
+/// # Usage
+///
+/// ```bash
+/// neolink dump-config --config=config.toml
+/// ```
+///
+use anyhow::{Context, Result};
+
+mod cmdline;
+
+use super::config::Config;
+pub(crate) use cmdline::Opt;
+
+/// Entry point for the dump-config subcommand
+///
+/// Opt is the command line options
+pub(crate) fn main(opt: Opt, mut config: Config) -> Result<()> {
+    if !opt.show_passwords {
+        for camera in config.cameras.iter_mut() {
+            if camera.password.is_some() {
+                camera.password = Some("********".to_string());
+            }
+        }
+        for user in config.users.iter_mut() {
+            user.pass = "********".to_string();
+        }
+    }
+
+    // Going through a toml Value puts the plain values before the tables,
+    // which the toml serializer needs
+    let value = toml::Value::try_from(&config).context("Unable to convert the config to toml")?;
+    let toml = toml::to_string_pretty(&value).context("Unable to write the config as toml")?;
+    println!("{}", toml);
+
+    Ok(())
+}
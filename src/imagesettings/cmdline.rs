@@ -0,0 +1,53 @@
+use anyhow::{anyhow, Result};
+use structopt::StructOpt;
+
+/// The anti-flicker modes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AntiFlicker {
+    /// Anti-flicker is disabled
+    Off,
+    /// Match 50Hz mains lighting
+    Hz50,
+    /// Match 60Hz mains lighting
+    Hz60,
+}
+
+fn antiflicker_parse(src: &str) -> Result<AntiFlicker> {
+    match src {
+        "off" => Ok(AntiFlicker::Off),
+        "50hz" | "50" => Ok(AntiFlicker::Hz50),
+        "60hz" | "60" => Ok(AntiFlicker::Hz60),
+        _ => Err(anyhow!(
+            "Could not understand {}, check your input, should be off, 50hz or 60hz",
+            src
+        )),
+    }
+}
+
+/// The image-settings command will get or set the brightness, contrast etc of the image
+///
+/// All values are in the range 0 to 255. If no values are given the current settings
+/// are printed
+#[derive(StructOpt, Debug)]
+pub struct Opt {
+    /// The name of the camera. Must be a name in the config
+    pub camera: String,
+    /// The brightness of the image
+    #[structopt(long)]
+    pub brightness: Option<i32>,
+    /// The contrast of the image
+    #[structopt(long)]
+    pub contrast: Option<i32>,
+    /// The saturation of the image
+    #[structopt(long)]
+    pub saturation: Option<i32>,
+    /// The hue of the image
+    #[structopt(long)]
+    pub hue: Option<i32>,
+    /// The sharpness of the image
+    #[structopt(long)]
+    pub sharpness: Option<i32>,
+    /// Stop mains lighting from flickering in the image
+    #[structopt(long, parse(try_from_str = antiflicker_parse), value_name = "off|50hz|60hz")]
+    pub anti_flicker: Option<AntiFlicker>,
+}
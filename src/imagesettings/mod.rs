@@ -0,0 +1,121 @@
+///
+/// # Neolink Image Settings
+///
+/// This module handles the image settings such as brightness
+/// and contrast
+///
+/// Values outside of the range 0 to 255 are clamped to that range
+///
+/// # Usage
+///
+/// ```bash
+/// # To print the current settings
+/// neolink image-settings --config=config.toml CameraName
+/// # To change the brightness and contrast
+/// neolink image-settings --config=config.toml CameraName --brightness=150 --contrast=100
+/// # To stop 50Hz mains lighting from flickering
+/// neolink image-settings --config=config.toml CameraName --anti-flicker=50hz
+/// ```
+///
+use anyhow::{anyhow, Context, Result};
+use log::*;
+use neolink_core::bc::xml::PowerLineFrequency;
+
+mod cmdline;
+
+use super::config::Config;
+use crate::utils::find_and_connect;
+use cmdline::AntiFlicker;
+pub(crate) use cmdline::Opt;
+
+/// Entry point for the image-settings subcommand
+///
+/// Opt is the command line options
+pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
+    let camera = find_and_connect(&config, &opt.camera)?;
+
+    let (mut video_input, mut input_advance_cfg) = camera
+        .get_image_settings()
+        .context("Unable to get camera image settings")?;
+
+    let changes = [
+        (opt.brightness, &mut video_input.bright),
+        (opt.contrast, &mut video_input.contrast),
+        (opt.saturation, &mut video_input.saturation),
+        (opt.hue, &mut video_input.hue),
+    ];
+
+    let mut changed = false;
+    for (value, setting) in changes {
+        if let Some(value) = value {
+            *setting = clamp_setting(value);
+            changed = true;
+        }
+    }
+    // Not all cameras report a sharpness
+    if let Some(sharpness) = opt.sharpness {
+        video_input.sharpen = Some(clamp_setting(sharpness));
+        changed = true;
+    }
+
+    // The InputAdvanceCfg is only sent back when it was changed as we only
+    // understand part of it
+    let mut advance_changed = None;
+    if let Some(anti_flicker) = opt.anti_flicker {
+        let mut cfg = input_advance_cfg
+            .take()
+            .ok_or_else(|| anyhow!("Camera did not report its anti-flicker settings"))?;
+        let power_line_frequency =
+            cfg.power_line_frequency
+                .get_or_insert_with(|| PowerLineFrequency {
+                    mode: "50hz".to_string(),
+                    enable: 0,
+                });
+        match anti_flicker {
+            AntiFlicker::Off => power_line_frequency.enable = 0,
+            AntiFlicker::Hz50 => {
+                power_line_frequency.mode = "50hz".to_string();
+                power_line_frequency.enable = 1;
+            }
+            AntiFlicker::Hz60 => {
+                power_line_frequency.mode = "60hz".to_string();
+                power_line_frequency.enable = 1;
+            }
+        }
+        advance_changed = Some(cfg);
+        changed = true;
+    }
+
+    if changed {
+        camera
+            .set_image_settings(video_input, advance_changed)
+            .context("Unable to set camera image settings")?;
+    } else {
+        info!(
+            "{}: brightness={} contrast={} saturation={} hue={}",
+            opt.camera,
+            video_input.bright,
+            video_input.contrast,
+            video_input.saturation,
+            video_input.hue,
+        );
+        if let Some(sharpen) = video_input.sharpen {
+            info!("{}: sharpness={}", opt.camera, sharpen);
+        }
+        if let Some(PowerLineFrequency { mode, enable }) = input_advance_cfg
+            .as_ref()
+            .and_then(|cfg| cfg.power_line_frequency.as_ref())
+        {
+            let state = if *enable == 0 { "off" } else { mode.as_str() };
+            info!("{}: anti-flicker={}", opt.camera, state);
+        }
+    }
+    Ok(())
+}
+
+fn clamp_setting(value: i32) -> u8 {
+    if !(0..=255).contains(&value) {
+        warn!("Image setting {} is out of range, clamping to 0-255", value);
+    }
+    value.clamp(0, 255) as u8
+}
@@ -0,0 +1,12 @@
+use structopt::StructOpt;
+
+/// The ir-light command will control the IR lights used for night vision
+#[derive(StructOpt, Debug)]
+pub struct Opt {
+    /// The name of the camera to change the lights of. Must be a name in the config
+    pub camera: String,
+    /// Force the lights on or off, or let the camera's light sensor decide with auto.
+    /// If not given the current state is printed
+    #[structopt(name = "on|off|auto", possible_values(&["on", "off", "auto"]))]
+    pub state: Option<String>,
+}
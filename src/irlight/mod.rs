@@ -0,0 +1,61 @@
+///
+/// # Neolink IR Light
+///
+/// This module handles the controls of the red IR lights
+/// used for night vision
+///
+/// By default the camera switches them with its own light sensor.
+/// They can instead be forced on or off from an external trigger,
+/// for example a cron job at the astronomical sunset and sunrise.
+///
+/// # Usage
+///
+/// ```bash
+/// # To force the IR lights on
+/// neolink ir-light --config=config.toml CameraName on
+/// # Or off
+/// neolink ir-light --config=config.toml CameraName off
+/// # To let the camera's light sensor decide again
+/// neolink ir-light --config=config.toml CameraName auto
+/// # To print the current state
+/// neolink ir-light --config=config.toml CameraName
+/// ```
+///
+use anyhow::{Context, Result};
+use log::*;
+use neolink_core::bc_protocol::LightState;
+
+mod cmdline;
+
+use super::config::Config;
+use crate::utils::find_and_connect;
+pub(crate) use cmdline::Opt;
+
+/// Entry point for the ir-light subcommand
+///
+/// Opt is the command line options
+pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
+    let camera = find_and_connect(&config, &opt.camera)?;
+
+    if let Some(state) = opt.state {
+        let state = match state.as_str() {
+            "on" => LightState::On,
+            "off" => LightState::Off,
+            _ => LightState::Auto,
+        };
+        camera
+            .irled_light_set(state)
+            .context("Unable to set camera IR light state")?;
+    } else {
+        let led_state = camera
+            .get_ledstate()
+            .context("Unable to get camera IR light state")?;
+        let state = match led_state.state.as_str() {
+            "open" => "on",
+            "close" => "off",
+            other => other,
+        };
+        info!("{}: IR lights are {}", opt.camera, state);
+    }
+    Ok(())
+}
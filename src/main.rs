@@ -20,27 +20,57 @@
 //!
 //! Neolink source code is available online at <https://github.com/thirtythreeforty/neolink>
 //!
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use env_logger::Env;
 use log::*;
 use std::fs;
+use std::io::Write;
 use structopt::StructOpt;
 use validator::Validate;
 
+mod capabilities;
 mod cmdline;
 mod config;
+mod dumpconfig;
+mod imagesettings;
+mod irlight;
+mod norm;
+mod osd;
 mod pir;
+mod profile;
 mod reboot;
+mod recordschedule;
+mod rtmp;
 mod rtsp;
+mod setname;
 mod statusled;
 mod talk;
+mod testconfig;
 mod utils;
+mod versioncheck;
 
 use cmdline::{Command, Opt};
 use config::Config;
 
 fn main() -> Result<()> {
-    env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
+    let opt = Opt::from_args();
+
+    let mut logger = env_logger::Builder::from_env(Env::default().default_filter_or("info"));
+    if opt.log_format == "json" {
+        logger.format(|buf, record| {
+            writeln!(
+                buf,
+                "{}",
+                serde_json::json!({
+                    "timestamp": buf.timestamp().to_string(),
+                    "level": record.level().to_string(),
+                    "target": record.target(),
+                    "message": record.args().to_string(),
+                })
+            )
+        });
+    }
+    logger.init();
 
     info!(
         "Neolink {} {}",
@@ -48,26 +78,45 @@ fn main() -> Result<()> {
         env!("NEOLINK_PROFILE")
     );
 
-    let opt = Opt::from_args();
-
     let conf_path = opt.config.context("Must supply --config file")?;
-    let config: Config = toml::from_str(
+    let mut config: Config = toml::from_str(
         &fs::read_to_string(&conf_path)
             .with_context(|| format!("Failed to read {:?}", conf_path))?,
     )
     .with_context(|| format!("Failed to parse the {:?} config file", conf_path))?;
 
+    if let Some(channel_id) = opt.channel {
+        // This is for spot checking one device, the rtsp server would move every camera
+        // onto the same channel
+        if matches!(opt.cmd, None | Some(Command::Rtsp(_))) {
+            return Err(anyhow!(
+                "--channel cannot be used with rtsp, set the channel_id of each camera in the config instead"
+            ));
+        }
+        for camera_config in config.cameras.iter_mut() {
+            camera_config.channel_id = channel_id;
+        }
+    }
+
     config
         .validate()
         .with_context(|| format!("Failed to validate the {:?} config file", conf_path))?;
 
+    if let Some(dump_dir) = &opt.dump_packets {
+        fs::create_dir_all(dump_dir)
+            .with_context(|| format!("Failed to create the packet dump dir {:?}", dump_dir))?;
+        for camera_config in config.cameras.iter_mut() {
+            camera_config.dump_packets = Some(dump_dir.clone());
+        }
+    }
+
     match opt.cmd {
         None => {
             warn!(
                 "Deprecated command line option. Please use: `neolink rtsp --config={:?}`",
                 config
             );
-            rtsp::main(rtsp::Opt {}, config)?;
+            rtsp::main(rtsp::Opt { once: None }, config)?;
         }
         Some(Command::Rtsp(opts)) => {
             rtsp::main(opts, config)?;
@@ -84,6 +133,42 @@ fn main() -> Result<()> {
         Some(Command::Talk(opts)) => {
             talk::main(opts, config)?;
         }
+        Some(Command::ImageSettings(opts)) => {
+            imagesettings::main(opts, config)?;
+        }
+        Some(Command::Capabilities(opts)) => {
+            capabilities::main(opts, config)?;
+        }
+        Some(Command::Osd(opts)) => {
+            osd::main(opts, config)?;
+        }
+        Some(Command::Profile(opts)) => {
+            profile::main(opts, config)?;
+        }
+        Some(Command::Norm(opts)) => {
+            norm::main(opts, config)?;
+        }
+        Some(Command::Rtmp(opts)) => {
+            rtmp::main(opts, config)?;
+        }
+        Some(Command::IrLight(opts)) => {
+            irlight::main(opts, config)?;
+        }
+        Some(Command::TestConfig(opts)) => {
+            testconfig::main(opts, config)?;
+        }
+        Some(Command::SetName(opts)) => {
+            setname::main(opts, config)?;
+        }
+        Some(Command::VersionCheck(opts)) => {
+            versioncheck::main(opts, config)?;
+        }
+        Some(Command::DumpConfig(opts)) => {
+            dumpconfig::main(opts, config)?;
+        }
+        Some(Command::RecordSchedule(opts)) => {
+            recordschedule::main(opts, config)?;
+        }
     }
 
     Ok(())
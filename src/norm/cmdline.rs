@@ -0,0 +1,11 @@
+use structopt::StructOpt;
+
+/// The norm command will get or set the video standard (NTSC/PAL) of the camera
+#[derive(StructOpt, Debug)]
+pub struct Opt {
+    /// The name of the camera. Must be a name in the config
+    pub camera: String,
+    /// The video standard to set. If not given the current one is printed
+    #[structopt(possible_values(&["NTSC", "PAL"]))]
+    pub norm: Option<String>,
+}
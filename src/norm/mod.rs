@@ -0,0 +1,41 @@
+///
+/// # Neolink Norm
+///
+/// This module handles the video standard of the camera.
+/// This is either NTSC or PAL and controls the frame rate
+/// of cameras that are connected through an analog DVR
+///
+/// # Usage
+///
+/// ```bash
+/// # To print the current norm
+/// neolink norm --config=config.toml CameraName
+/// # To change to PAL
+/// neolink norm --config=config.toml CameraName PAL
+/// ```
+///
+use anyhow::{Context, Result};
+use log::*;
+
+mod cmdline;
+
+use super::config::Config;
+use crate::utils::find_and_connect;
+pub(crate) use cmdline::Opt;
+
+/// Entry point for the norm subcommand
+///
+/// Opt is the command line options
+pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
+    let camera = find_and_connect(&config, &opt.camera)?;
+
+    if let Some(norm) = opt.norm {
+        camera
+            .set_norm(&norm)
+            .context("Unable to set camera norm")?;
+    } else {
+        let norm = camera.get_norm().context("Unable to get camera norm")?;
+        info!("{}: Norm is {}", opt.camera, norm);
+    }
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use anyhow::{anyhow, Result};
+use structopt::StructOpt;
+
+fn onoff_parse(src: &str) -> Result<bool> {
+    match src {
+        "true" | "on" | "yes" => Ok(true),
+        "false" | "off" | "no" => Ok(false),
+        _ => Err(anyhow!(
+            "Could not understand {}, check your input, should be true/false, on/off or yes/no",
+            src
+        )),
+    }
+}
+
+fn position_parse(src: &str) -> Result<(u32, u32)> {
+    let mut parts = src.splitn(2, ',');
+    match (parts.next(), parts.next()) {
+        (Some(x), Some(y)) => Ok((x.trim().parse()?, y.trim().parse()?)),
+        _ => Err(anyhow!(
+            "Could not understand {}, check your input, should be topLeftX,topLeftY e.g. 65536,65536",
+            src
+        )),
+    }
+}
+
+/// The osd command will control the text drawn over the video
+#[derive(StructOpt, Debug)]
+pub struct Opt {
+    /// The name of the camera. Must be a name in the config
+    pub camera: String,
+    /// The name to draw over the video
+    #[structopt(long)]
+    pub name: Option<String>,
+    /// Whether to draw the name over the video
+    #[structopt(long, parse(try_from_str = onoff_parse), value_name = "on|off")]
+    pub show_name: Option<bool>,
+    /// Whether to draw the date and time over the video
+    #[structopt(long, parse(try_from_str = onoff_parse), value_name = "on|off")]
+    pub show_time: Option<bool>,
+    /// Position of the name as the camera's `topLeftX,topLeftY` values.
+    /// Observed values are `65536,65536` and `65537,1`
+    #[structopt(long, parse(try_from_str = position_parse), value_name = "x,y")]
+    pub name_position: Option<(u32, u32)>,
+    /// Position of the date and time as the camera's `topLeftX,topLeftY` values.
+    /// Observed values are `65536,65536` and `65537,1`
+    #[structopt(long, parse(try_from_str = position_parse), value_name = "x,y")]
+    pub time_position: Option<(u32, u32)>,
+}
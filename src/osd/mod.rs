@@ -0,0 +1,74 @@
+///
+/// # Neolink OSD
+///
+/// This module handles the on screen display, the camera
+/// name and the date/time that are drawn over the video
+///
+/// # Usage
+///
+/// ```bash
+/// # To print the current settings
+/// neolink osd --config=config.toml CameraName
+/// # To change the name and draw it on the video
+/// neolink osd --config=config.toml CameraName --name="Front Porch" --show-name=on
+/// # To stop drawing the date and time
+/// neolink osd --config=config.toml CameraName --show-time=off
+/// # To move the name and the date and time
+/// neolink osd --config=config.toml CameraName --name-position=65536,65536 --time-position=65537,1
+/// ```
+///
+use anyhow::{Context, Result};
+use log::*;
+
+mod cmdline;
+
+use super::config::Config;
+use crate::utils::find_and_connect;
+pub(crate) use cmdline::Opt;
+
+/// Entry point for the osd subcommand
+///
+/// Opt is the command line options
+pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
+    let camera = find_and_connect(&config, &opt.camera)?;
+
+    let (mut osd_channel_name, mut osd_datetime) =
+        camera.get_osd().context("Unable to get camera OSD")?;
+
+    if opt.name.is_none()
+        && opt.show_name.is_none()
+        && opt.show_time.is_none()
+        && opt.name_position.is_none()
+        && opt.time_position.is_none()
+    {
+        info!("{:#?}", osd_channel_name);
+        info!("{:#?}", osd_datetime);
+        return Ok(());
+    }
+
+    if let Some(name) = opt.name {
+        osd_channel_name.name = name;
+    }
+    if let Some(show_name) = opt.show_name {
+        osd_channel_name.enable = show_name as u8;
+    }
+    if let Some(show_time) = opt.show_time {
+        osd_datetime.enable = show_time as u8;
+    }
+    if let Some((x, y)) = opt.name_position {
+        osd_channel_name.top_left_x = x;
+        osd_channel_name.top_left_y = y;
+    }
+    if let Some((x, y)) = opt.time_position {
+        osd_datetime.top_left_x = x;
+        osd_datetime.top_left_y = y;
+    }
+    // These are only sent by the camera
+    osd_datetime.width = None;
+    osd_datetime.height = None;
+
+    camera
+        .set_osd(osd_channel_name, osd_datetime)
+        .context("Unable to set camera OSD")?;
+    Ok(())
+}
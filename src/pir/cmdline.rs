@@ -1,17 +1,6 @@
-use anyhow::{anyhow, Result};
+use crate::utils::{hours_parse, onoff_parse};
 use structopt::StructOpt;
 
-fn onoff_parse(src: &str) -> Result<bool> {
-    match src {
-        "true" | "on" | "yes" => Ok(true),
-        "false" | "off" | "no" => Ok(false),
-        _ => Err(anyhow!(
-            "Could not understand {}, check your input, should be true/false, on/off or yes/no",
-            src
-        )),
-    }
-}
-
 /// The pir command will control the PIR status of the camera
 #[derive(StructOpt, Debug)]
 pub struct Opt {
@@ -19,5 +8,11 @@ pub struct Opt {
     pub camera: String,
     /// Whether to turn the PIR ON or OFF
     #[structopt(parse(try_from_str = onoff_parse), name = "on|off")]
-    pub on: bool,
+    pub on: Option<bool>,
+    /// Set the sensitivity of the PIR
+    #[structopt(long)]
+    pub sensitivity: Option<u8>,
+    /// Only activate the PIR between these hours of every day e.g. 18-23
+    #[structopt(long, parse(try_from_str = hours_parse), value_name = "BEGIN-END")]
+    pub schedule: Option<(u8, u8)>,
 }
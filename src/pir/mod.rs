@@ -11,14 +11,21 @@
 /// neolink pir --config=config.toml CameraName on
 /// # Or off
 /// neolink pir --config=config.toml CameraName off
+/// # To set the sensitivity
+/// neolink pir --config=config.toml CameraName --sensitivity=16
+/// # To only activate it between 18:00 and 23:59 each day
+/// neolink pir --config=config.toml CameraName --schedule=18-23
+/// # To print the current settings
+/// neolink pir --config=config.toml CameraName
 /// ```
 ///
 use anyhow::{Context, Result};
+use log::*;
 
 mod cmdline;
 
 use super::config::Config;
-use crate::utils::find_and_connect;
+use crate::utils::{every_day, find_and_connect};
 pub(crate) use cmdline::Opt;
 
 /// Entry point for the pir subcommand
@@ -27,8 +34,27 @@ pub(crate) use cmdline::Opt;
 pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
     let mut camera = find_and_connect(&config, &opt.camera)?;
 
-    camera
-        .pir_set(opt.on)
-        .context("Unable to set camera PIR state")?;
+    if let Some(on) = opt.on {
+        camera
+            .pir_set(on)
+            .context("Unable to set camera PIR state")?;
+    }
+    if let Some(sensitivity) = opt.sensitivity {
+        camera
+            .set_pir_sensitivity(sensitivity)
+            .context("Unable to set camera PIR sensitivity")?;
+    }
+    if let Some((begin_hour, end_hour)) = opt.schedule {
+        camera
+            .set_pir_schedule(every_day(begin_hour, end_hour))
+            .context("Unable to set camera PIR schedule")?;
+    }
+
+    if opt.on.is_none() && opt.sensitivity.is_none() && opt.schedule.is_none() {
+        let pir_state = camera
+            .get_pirstate()
+            .context("Unable to get camera PIR state")?;
+        info!("{:#?}", pir_state);
+    }
     Ok(())
 }
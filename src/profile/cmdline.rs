@@ -0,0 +1,19 @@
+use structopt::StructOpt;
+
+/// The profile command will write the encoding settings from the config to the camera
+#[derive(StructOpt, Debug)]
+pub struct Opt {
+    /// The name of the camera. Must be a name in the config
+    pub camera: String,
+    #[structopt(subcommand)]
+    pub cmd: Option<ProfileCommand>,
+}
+
+#[derive(StructOpt, Debug)]
+pub enum ProfileCommand {
+    /// Write the resolution, bitrate and fps of a profile to the camera
+    Apply {
+        /// The name of the profile. Must be a name in the config
+        profile: String,
+    },
+}
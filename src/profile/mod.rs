@@ -0,0 +1,52 @@
+///
+/// # Neolink Profile
+///
+/// This module handles the encoding profiles, the resolution,
+/// bitrate and fps of the streams, that are named in the config
+///
+/// # Usage
+///
+/// ```bash
+/// # To print the current resolution, bitrate and fps
+/// neolink profile --config=config.toml CameraName
+/// # To write the profile named "preferred" to the camera
+/// neolink profile --config=config.toml CameraName apply preferred
+/// ```
+///
+use anyhow::{Context, Result};
+use log::*;
+
+mod cmdline;
+
+use super::config::Config;
+use crate::utils::{apply_profile, find_and_connect, find_profile_by_name};
+pub(crate) use cmdline::Opt;
+use cmdline::ProfileCommand;
+
+/// Entry point for the profile subcommand
+///
+/// Opt is the command line options
+pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
+    let camera = find_and_connect(&config, &opt.camera)?;
+
+    match opt.cmd {
+        None => {
+            let compression = camera
+                .get_compression()
+                .context("Unable to get camera compression")?;
+            info!("{:#?}", compression);
+        }
+        Some(ProfileCommand::Apply { profile }) => {
+            let profile = find_profile_by_name(&config.profiles, &profile)?;
+            if apply_profile(&camera, profile)? {
+                info!("{}: Applied profile {}", opt.camera, profile.name);
+            } else {
+                info!(
+                    "{}: Camera already matches profile {}",
+                    opt.camera, profile.name
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use crate::utils::{hours_parse, onoff_parse};
+use structopt::StructOpt;
+
+/// The record-schedule command will get or set when the camera records
+///
+/// If no values are given the current schedule is printed
+#[derive(StructOpt, Debug)]
+pub struct Opt {
+    /// The name of the camera. Must be a name in the config
+    pub camera: String,
+    /// Whether to turn recording ON or OFF
+    #[structopt(parse(try_from_str = onoff_parse), name = "on|off")]
+    pub on: Option<bool>,
+    /// Record continuously between these hours of every day e.g. 9-17
+    #[structopt(long, parse(try_from_str = hours_parse), value_name = "BEGIN-END")]
+    pub continuous: Option<(u8, u8)>,
+    /// Record on motion between these hours of every day e.g. 0-23
+    #[structopt(long, parse(try_from_str = hours_parse), value_name = "BEGIN-END")]
+    pub motion: Option<(u8, u8)>,
+}
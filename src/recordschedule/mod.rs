@@ -0,0 +1,73 @@
+///
+/// # Neolink Record Schedule
+///
+/// This module handles the recording schedule of the camera
+///
+/// Each option replaces the schedule of that type of recording for every day
+/// of the week, the schedules of the other types are kept as they are
+///
+/// # Usage
+///
+/// ```bash
+/// # To print the current schedule
+/// neolink record-schedule --config=config.toml CameraName
+/// # To record continuously during business hours
+/// neolink record-schedule --config=config.toml CameraName --continuous=9-17
+/// # To record on motion overnight
+/// neolink record-schedule --config=config.toml CameraName --motion=18-23
+/// # To stop recording
+/// neolink record-schedule --config=config.toml CameraName off
+/// ```
+///
+use anyhow::{Context, Result};
+use log::*;
+use neolink_core::bc::xml::Schedule;
+
+mod cmdline;
+
+use super::config::Config;
+use crate::utils::{every_day, find_and_connect};
+pub(crate) use cmdline::Opt;
+
+/// The `alarmType` of the continuous recording schedule
+const CONTINUOUS_ALARM_TYPE: &str = "none";
+/// The `alarmType` of the motion recording schedule
+const MOTION_ALARM_TYPE: &str = "MD";
+
+/// Entry point for the record-schedule subcommand
+///
+/// Opt is the command line options
+pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
+    let camera = find_and_connect(&config, &opt.camera)?;
+
+    let mut record = camera
+        .get_record_schedule()
+        .context("Unable to get camera record schedule")?;
+
+    if opt.on.is_none() && opt.continuous.is_none() && opt.motion.is_none() {
+        info!("{:#?}", record);
+        return Ok(());
+    }
+
+    if let Some(on) = opt.on {
+        record.enable = on as u8;
+    }
+    for (hours, alarm_type) in [
+        (opt.continuous, CONTINUOUS_ALARM_TYPE),
+        (opt.motion, MOTION_ALARM_TYPE),
+    ] {
+        if let Some((begin_hour, end_hour)) = hours {
+            let schedules = &mut record.schedule_list.schedule;
+            schedules.retain(|schedule| schedule.alarm_type != alarm_type);
+            schedules.push(Schedule {
+                alarm_type: alarm_type.to_string(),
+                time_block_list: every_day(begin_hour, end_hour),
+            });
+        }
+    }
+
+    camera
+        .set_record_schedule(record)
+        .context("Unable to set camera record schedule")?;
+    Ok(())
+}
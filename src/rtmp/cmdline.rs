@@ -0,0 +1,18 @@
+use structopt::StructOpt;
+
+/// The rtmp command will push the video of a camera to an rtmp server
+#[derive(StructOpt, Debug)]
+pub struct Opt {
+    /// The name of the camera. Must be a name in the config
+    pub camera: String,
+    /// The rtmp url to publish to, e.g. `rtmp://example.com/live/streamkey`
+    #[structopt(long)]
+    pub url: String,
+    /// The stream of the camera to publish
+    #[structopt(
+        long,
+        default_value = "mainStream",
+        possible_values(&["mainStream", "subStream", "externStream"])
+    )]
+    pub stream: String,
+}
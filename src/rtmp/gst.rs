@@ -0,0 +1,131 @@
+use anyhow::{anyhow, Context, Result};
+use gstreamer::{parse_launch, prelude::*, Buffer, ClockTime, MessageView, Pipeline, State};
+use gstreamer_app::AppSrc;
+use log::*;
+use neolink_core::{
+    bc_protocol::{Error, StreamOutput, StreamOutputError},
+    bcmedia::model::*,
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Pushes the video frames of the camera into an rtmp pipeline
+///
+/// The pipeline is only built once the first key frame arrives since
+/// until then we do not know if the camera sends H264 or H265
+pub(super) struct RtmpOutput {
+    url: String,
+    pipeline: Option<(Pipeline, AppSrc)>,
+    failed: Arc<AtomicBool>,
+}
+
+impl RtmpOutput {
+    pub(super) fn new(url: &str) -> Result<Self> {
+        gstreamer::init().context("Unable to start gstreamer")?;
+        Ok(Self {
+            url: url.to_string(),
+            pipeline: None,
+            failed: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    fn start(&mut self, video_type: VideoType) -> Result<()> {
+        let launch_vid = match video_type {
+            VideoType::H264 => "h264parse",
+            VideoType::H265 => {
+                // FLV can only carry H264 so H265 has to be re-encoded which is CPU heavy
+                warn!("The camera sends H265 which rtmp does not support, it will be transcoded to H264");
+                "h265parse ! decodebin ! videoconvert ! x264enc tune=zerolatency ! h264parse"
+            }
+        };
+        let pipeline = parse_launch(&format!(
+            "appsrc name=vidsrc is-live=true block=true max-bytes=52428800 do-timestamp=true format=GST_FORMAT_TIME ! {} ! flvmux streamable=true ! rtmpsink name=sink",
+            launch_vid
+        ))
+        .context("Unable to create the rtmp pipeline, check your gstreamer plugins")?
+        .dynamic_cast::<Pipeline>()
+        .map_err(|_| anyhow!("The rtmp pipeline is not a pipeline"))?;
+
+        pipeline
+            .by_name("sink")
+            .expect("There should be a `sink`")
+            .set_property("location", &self.url)
+            .context("Unable to set the rtmp url")?;
+        let app_src = pipeline
+            .by_name("vidsrc")
+            .expect("There should be a `vidsrc`")
+            .dynamic_cast::<AppSrc>()
+            .map_err(|_| {
+                anyhow!("Cannot find appsrc in gstreamer, check your gstreamer plugins")
+            })?;
+
+        pipeline
+            .set_state(State::Playing)
+            .context("Unable to start the rtmp pipeline")?;
+
+        // Watch for the rtmp server hanging up on us
+        let bus = pipeline
+            .bus()
+            .expect("Pipeline without bus. Shouldn't happen!");
+        let failed = self.failed.clone();
+        std::thread::spawn(move || {
+            for msg in bus.iter_timed(ClockTime::NONE) {
+                match msg.view() {
+                    MessageView::Eos(..) => break,
+                    MessageView::Error(err) => {
+                        error!("Error from the rtmp pipeline: {}", err.error());
+                        failed.store(true, Ordering::SeqCst);
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        });
+
+        self.pipeline = Some((pipeline, app_src));
+        Ok(())
+    }
+
+    fn write(&self, data: &[u8]) {
+        if let Some((_, app_src)) = &self.pipeline {
+            // Errors are reported on the bus which we are already watching
+            let _ = app_src.push_buffer(Buffer::from_slice(data.to_vec()));
+        }
+    }
+}
+
+impl StreamOutput for RtmpOutput {
+    fn stream_recv(&mut self, media: BcMedia) -> StreamOutputError {
+        if self.failed.load(Ordering::SeqCst) {
+            return Err(Error::Other("The rtmp pipeline failed"));
+        }
+        match media {
+            BcMedia::Iframe(payload) => {
+                if self.pipeline.is_none() {
+                    self.start(payload.video_type)
+                        .map_err(|e| Error::OtherString(format!("{:?}", e)))?;
+                }
+                self.write(&payload.data);
+            }
+            BcMedia::Pframe(payload) => {
+                // Frames before the first key frame cannot be decoded so are dropped
+                self.write(&payload.data);
+            }
+            _ => {
+                // Only the video is published
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl Drop for RtmpOutput {
+    fn drop(&mut self) {
+        if let Some((pipeline, app_src)) = self.pipeline.take() {
+            let _ = app_src.end_of_stream();
+            let _ = pipeline.set_state(State::Null);
+        }
+    }
+}
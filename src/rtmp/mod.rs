@@ -0,0 +1,61 @@
+///
+/// # Neolink RTMP
+///
+/// This module publishes the video of a camera to an rtmp server
+/// such as YouTube, Twitch or a private nginx-rtmp server
+///
+/// H265 cameras are transcoded to H264 as rtmp cannot carry H265.
+/// Only the video is published.
+///
+/// # Usage
+///
+/// ```bash
+/// neolink rtmp --config=config.toml --url=rtmp://example.com/live/streamkey CameraName
+/// # To publish the lower resolution stream
+/// neolink rtmp --config=config.toml --url=rtmp://example.com/live/streamkey --stream=subStream CameraName
+/// ```
+///
+use anyhow::{Context, Result};
+use log::*;
+use neolink_core::bc_protocol::Stream;
+
+mod cmdline;
+mod gst;
+
+use super::config::Config;
+use crate::utils::find_and_connect;
+pub(crate) use cmdline::Opt;
+use gst::RtmpOutput;
+
+/// Entry point for the rtmp subcommand
+///
+/// Opt is the command line options
+pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
+    let stream = match opt.stream.as_str() {
+        "subStream" => Stream::Sub,
+        "externStream" => Stream::Extern,
+        _ => Stream::Main,
+    };
+    let mut output = RtmpOutput::new(&opt.url)?;
+
+    let camera = find_and_connect(&config, &opt.camera)?;
+    info!(
+        "{}: Publishing {} to {}",
+        opt.camera,
+        opt.stream,
+        redact_url(&opt.url)
+    );
+    camera
+        .start_video(&mut output, stream)
+        .with_context(|| format!("Error while publishing {}", opt.camera))?;
+    Ok(())
+}
+
+/// Hides the stream key, which is the last part of the url, so it is not leaked in the logs
+fn redact_url(url: &str) -> String {
+    let path_start = url.find("://").map(|i| i + 3).unwrap_or(0);
+    match url[path_start..].rfind('/') {
+        Some(i) => format!("{}/<redacted>", &url[..path_start + i]),
+        None => url.to_string(),
+    }
+}
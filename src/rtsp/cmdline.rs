@@ -2,4 +2,9 @@ use structopt::StructOpt;
 
 /// The rtsp command will serve all cameras in the config over the rtsp protocol
 #[derive(StructOpt, Debug)]
-pub struct Opt {}
+pub struct Opt {
+    /// Serve only this camera until the first client disconnects then exit.
+    /// The exit code is nonzero if the camera could not be streamed
+    #[structopt(long, value_name = "CAMERA")]
+    pub once: Option<String>,
+}
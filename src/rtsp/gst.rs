@@ -3,16 +3,16 @@
 pub(crate) use self::maybe_app_src::MaybeAppSrc;
 // use super::adpcm::adpcm_to_pcm;
 // use super::errors::Error;
-use gstreamer::prelude::Cast;
-use gstreamer::{Bin, Structure};
+use gstreamer::prelude::{Cast, ElementExt, GstBinExt, PadExt};
+use gstreamer::{Bin, Caps, Element, GhostPad, Structure};
 use gstreamer_app::AppSrc;
-//use gstreamer_rtsp::RTSPLowerTrans;
-use gstreamer_rtsp::RTSPAuthMethod;
+use gstreamer_rtsp::{RTSPAuthMethod, RTSPLowerTrans, RTSPStatusCode};
 pub use gstreamer_rtsp_server::gio::{TlsAuthenticationMode, TlsCertificate};
 use gstreamer_rtsp_server::glib;
+use gstreamer_rtsp_server::glib::translate::{from_glib, from_glib_none, ToGlibPtr};
 use gstreamer_rtsp_server::prelude::*;
 use gstreamer_rtsp_server::{
-    RTSPAuth, RTSPMediaFactory, RTSPServer as GstRTSPServer, RTSPToken,
+    RTSPAuth, RTSPClient, RTSPContext, RTSPMediaFactory, RTSPServer as GstRTSPServer, RTSPToken,
     RTSP_PERM_MEDIA_FACTORY_ACCESS, RTSP_PERM_MEDIA_FACTORY_CONSTRUCT,
     RTSP_TOKEN_MEDIA_FACTORY_ROLE,
 };
@@ -25,19 +25,62 @@ use std::collections::HashSet;
 use std::fs;
 use std::io;
 use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 type Result<T> = std::result::Result<T, ()>;
 
 pub(crate) struct RtspServer {
     server: GstRTSPServer,
+    main_loop: glib::MainLoop,
+    shutdown: Arc<AtomicBool>,
+}
+
+/// Can be sent to another thread, such as a signal handler, to stop the server
+#[derive(Clone)]
+pub(crate) struct ShutdownHandle {
+    main_loop: glib::MainLoop,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl ShutdownHandle {
+    /// Stops serving clients and asks the camera streams to end
+    pub(crate) fn shutdown(&self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        self.main_loop.quit();
+    }
+
+    pub(crate) fn is_shutdown(&self) -> bool {
+        self.shutdown.load(Ordering::SeqCst)
+    }
 }
 
 pub(crate) struct GstOutputs {
     pub(crate) audsrc: MaybeAppSrc,
     pub(crate) vidsrc: MaybeAppSrc,
+    pub(crate) audonlysrc: MaybeAppSrc,
+    pub(crate) keyframesrc: MaybeAppSrc,
     video_format: Option<StreamFormat>,
     audio_format: Option<StreamFormat>,
+    // Re-encode H265 video as H264 for clients that cannot play H265
+    transcode_h264: bool,
+    audio_codec: AudioCodec,
+    // Stop the camera stream when the last client leaves
+    pub(crate) on_demand: bool,
+    // Extra elements to splice in before the video parser and before the payloader, the
+    // appended ones are shared with the media-configure handler that builds the payloader
+    // once the video format of the first stream is found
+    pipeline_prepend: Option<String>,
+    pipeline_append: Arc<Mutex<Option<String>>>,
+    // Woken when a client's media is configured so that on demand streams connect right away
+    media_tx: SyncSender<()>,
+    media_rx: Receiver<()>,
     factory: RTSPMediaFactory,
+    audio_factory: RTSPMediaFactory,
+    keyframe_factory: RTSPMediaFactory,
+    shutdown: Arc<AtomicBool>,
 }
 
 // The stream from the camera will be using one of these formats
@@ -56,8 +99,27 @@ enum StreamFormat {
     Adpcm(u16),
 }
 
+// The codec that the audio is sent to the rtsp clients in
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub(crate) enum AudioCodec {
+    // Raw 16bit PCM, this is played by almost all clients
+    Pcm,
+    // AAC, the camera's AAC is passed through and ADPCM is re-encoded
+    Aac,
+    // No audio track is offered
+    None,
+}
+
 impl StreamOutput for GstOutputs {
     fn stream_recv(&mut self, media: BcMedia) -> StreamOutputError {
+        if self.is_shutdown() {
+            // Stop the stream so that the camera is logged out
+            return Ok(false);
+        }
+        if self.on_demand && !self.has_client() {
+            // Nobody is watching anymore, stop talking to the camera
+            return Ok(false);
+        }
         match media {
             BcMedia::Iframe(payload) => {
                 let video_type = match payload.video_type {
@@ -65,7 +127,8 @@ impl StreamOutput for GstOutputs {
                     VideoType::H265 => StreamFormat::H265,
                 };
                 self.set_format(Some(video_type));
-                self.vidsrc.write_all(&payload.data)?;
+                self.vidsrc.write_frame(&payload.data, true)?;
+                self.keyframesrc.write_all(&payload.data)?;
             }
             BcMedia::Pframe(payload) => {
                 let video_type = match payload.video_type {
@@ -73,15 +136,17 @@ impl StreamOutput for GstOutputs {
                     VideoType::H265 => StreamFormat::H265,
                 };
                 self.set_format(Some(video_type));
-                self.vidsrc.write_all(&payload.data)?;
+                self.vidsrc.write_frame(&payload.data, false)?;
             }
             BcMedia::Aac(payload) => {
                 self.set_format(Some(StreamFormat::Aac));
                 self.audsrc.write_all(&payload.data)?;
+                self.audonlysrc.write_all(&payload.data)?;
             }
             BcMedia::Adpcm(payload) => {
                 self.set_format(Some(StreamFormat::Adpcm(payload.data.len() as u16)));
                 self.audsrc.write_all(&payload.data)?;
+                self.audonlysrc.write_all(&payload.data)?;
             }
             _ => {
                 //Ignore other BcMedia like InfoV1 and InfoV2
@@ -93,18 +158,88 @@ impl StreamOutput for GstOutputs {
 }
 
 impl GstOutputs {
-    pub(crate) fn from_appsrcs(vidsrc: MaybeAppSrc, audsrc: MaybeAppSrc) -> GstOutputs {
+    pub(crate) fn from_appsrcs(
+        vidsrc: MaybeAppSrc,
+        audsrc: MaybeAppSrc,
+        audonlysrc: MaybeAppSrc,
+        keyframesrc: MaybeAppSrc,
+        transcode_h264: bool,
+        audio_codec: AudioCodec,
+        shutdown: Arc<AtomicBool>,
+    ) -> GstOutputs {
+        let (media_tx, media_rx) = sync_channel(1);
         let result = GstOutputs {
             vidsrc,
             audsrc,
+            audonlysrc,
+            keyframesrc,
             video_format: None,
             audio_format: None,
+            transcode_h264,
+            audio_codec,
+            on_demand: false,
+            pipeline_prepend: None,
+            pipeline_append: Arc::new(Mutex::new(None)),
+            media_tx,
+            media_rx,
             factory: RTSPMediaFactory::new(),
+            audio_factory: RTSPMediaFactory::new(),
+            keyframe_factory: RTSPMediaFactory::new(),
+            shutdown,
         };
         result.apply_format();
+        result.connect_dynamic_video(&result.factory);
+        result.connect_dynamic_video(&result.keyframe_factory);
         result
     }
 
+    /// Sets the extra gstreamer elements to splice into the video pipeline
+    pub(crate) fn set_pipeline_elements(
+        &mut self,
+        prepend: Option<String>,
+        append: Option<String>,
+    ) {
+        self.pipeline_prepend = prepend;
+        *self.pipeline_append.lock().unwrap() = append;
+        self.apply_format();
+    }
+
+    /// Waits up to `timeout` for a client's media to be configured
+    pub(crate) fn wait_for_client(&self, timeout: Duration) {
+        let _ = self.media_rx.recv_timeout(timeout);
+    }
+
+    /// Sets how much the rtsp server buffers, in milliseconds
+    pub(crate) fn set_latency(&self, latency_ms: u32) {
+        self.factory.set_latency(latency_ms);
+        self.audio_factory.set_latency(latency_ms);
+        self.keyframe_factory.set_latency(latency_ms);
+    }
+
+    /// Sends EOS to all the appsrcs so that the clients know the stream has ended
+    pub(crate) fn end_of_stream(&mut self) {
+        self.vidsrc.on_stream_error();
+        self.audsrc.on_stream_error();
+        self.audonlysrc.on_stream_error();
+        self.keyframesrc.on_stream_error();
+    }
+
+    pub(crate) fn is_shutdown(&self) -> bool {
+        self.shutdown.load(Ordering::SeqCst)
+    }
+
+    /// True while any of the mounts of this stream has a client
+    pub(crate) fn has_client(&mut self) -> bool {
+        // Evaluate all of them so that each picks up its new AppSrc
+        let has_client = [
+            self.vidsrc.has_src(),
+            self.audsrc.has_src(),
+            self.audonlysrc.has_src(),
+            self.keyframesrc.has_src(),
+        ];
+        has_client.iter().any(|&has| has)
+    }
+
     fn set_format(&mut self, format: Option<StreamFormat>) {
         match format {
             Some(StreamFormat::H264) | Some(StreamFormat::H265) => {
@@ -124,36 +259,179 @@ impl GstOutputs {
     }
 
     fn apply_format(&self) {
+        let prepend = self
+            .pipeline_prepend
+            .as_ref()
+            .map(|elements| format!("{} ! ", elements))
+            .unwrap_or_default();
         let launch_vid = match self.video_format {
-            Some(StreamFormat::H264) => {
-                "! queue silent=true max-size-bytes=10485760  min-threshold-bytes=1024 ! h264parse ! rtph264pay name=pay0"
-            }
-            Some(StreamFormat::H265) => {
-                "! queue silent=true  max-size-bytes=10485760  min-threshold-bytes=1024 ! h265parse ! rtph265pay name=pay0"
+            Some(format) => {
+                let append = self.pipeline_append.lock().unwrap();
+                format!(
+                    "! queue silent=true max-size-bytes=10485760 min-threshold-bytes=1024 ! {}{}",
+                    prepend,
+                    launch_pay(format, self.transcode_h264, append.as_deref(), "pay0")
+                )
             }
-            _ => "! fakesink",
+            // Until the first stream the format is unknown, so the payloader is added by
+            // `connect_dynamic_video` once the first frames are typefound. The client waits
+            // for it rather than being served an empty stream
+            None => format!(
+                "! queue silent=true max-size-bytes=10485760 min-threshold-bytes=1024 ! {}( name=dynpay0 typefind name=vidtypefind )",
+                prepend
+            ),
         };
 
-        let launch_aud = match self.audio_format {
-            Some(StreamFormat::Adpcm(block_size)) => format!("caps=audio/x-adpcm,layout=dvi,block_align={},channels=1,rate=8000 ! queue silent=true max-size-bytes=10485760 min-threshold-bytes=1024 ! adpcmdec  ! audioconvert ! rtpL16pay name=pay1", block_size), // DVI4 is converted to pcm in the appsrc
-            Some(StreamFormat::Aac) => "! queue silent=true max-size-bytes=10485760 min-threshold-bytes=1024 ! aacparse ! decodebin ! audioconvert ! rtpL16pay name=pay1 name=pay1".to_string(),
-            _ => "! fakesink".to_string(),
-        };
+        let launch_aud = self.launch_aud("pay1");
 
         self.factory.set_launch(
             &vec![
             "( ",
             "appsrc name=vidsrc is-live=true block=true emit-signals=false max-bytes=52428800 do-timestamp=true format=GST_FORMAT_TIME", // 50MB max size so that it won't grow to infinite if the queue blocks
-            launch_vid,
+            &launch_vid,
             "appsrc name=audsrc is-live=true block=true emit-signals=false max-bytes=52428800 do-timestamp=true format=GST_FORMAT_TIME", // 50MB max size so that it won't grow to infinite if the queue blocks
             &launch_aud,
             ")"
         ]
             .join(" "),
         );
+
+        // The audio only stream has no video so its payloader is the first one
+        self.audio_factory.set_launch(
+            &[
+            "( ",
+            "appsrc name=audsrc is-live=true block=true emit-signals=false max-bytes=52428800 do-timestamp=true format=GST_FORMAT_TIME", // 50MB max size so that it won't grow to infinite if the queue blocks
+            &self.launch_aud("pay0"),
+            ")"
+        ]
+            .join(" "),
+        );
+
+        // The keyframe stream is only fed the iframes so it plays at the GOP rate
+        self.keyframe_factory.set_launch(
+            &[
+            "( ",
+            "appsrc name=vidsrc is-live=true block=true emit-signals=false max-bytes=52428800 do-timestamp=true format=GST_FORMAT_TIME", // 50MB max size so that it won't grow to infinite if the queue blocks
+            &launch_vid,
+            ")"
+        ]
+            .join(" "),
+        );
+    }
+
+    // When a media is made before the video format is known, links the parser and
+    // payloader into its dynamic payloader once the format is found
+    fn connect_dynamic_video(&self, factory: &RTSPMediaFactory) {
+        let transcode_h264 = self.transcode_h264;
+        let pipeline_append = self.pipeline_append.clone();
+        factory.connect_media_configure(move |_factory, media| {
+            let bin = media
+                .element()
+                .expect("Media should have an element")
+                .dynamic_cast::<Bin>()
+                .expect("Media source's element should be a bin");
+            let (dynpay, typefind) = match (bin.by_name("dynpay0"), bin.by_name("vidtypefind")) {
+                (Some(dynpay), Some(typefind)) => (dynpay, typefind),
+                // The format was already known when the media was made
+                _ => return,
+            };
+            let pipeline_append = pipeline_append.clone();
+            let connected = typefind.connect("have-type", false, move |values| {
+                let caps = values[2].get::<Caps>().ok()?;
+                let append = pipeline_append.lock().unwrap().clone();
+                if let Err(e) = link_dynamic_pay(&dynpay, &caps, transcode_h264, append.as_deref())
+                {
+                    error!(
+                        "RTSP: Unable to build the video pipeline for {}: {}",
+                        caps, e
+                    );
+                }
+                None
+            });
+            if connected.is_err() {
+                error!("RTSP: Unable to wait for the video format");
+            }
+        });
+    }
+
+    fn launch_aud(&self, pay_name: &str) -> String {
+        match (self.audio_format, self.audio_codec) {
+            (_, AudioCodec::None) => "! fakesink".to_string(),
+            (Some(StreamFormat::Adpcm(block_size)), AudioCodec::Pcm) => format!("caps=audio/x-adpcm,layout=dvi,block_align={},channels=1,rate=8000 ! queue silent=true max-size-bytes=10485760 min-threshold-bytes=1024 ! adpcmdec  ! audioconvert ! rtpL16pay name={}", block_size, pay_name), // DVI4 is converted to pcm in the appsrc
+            (Some(StreamFormat::Adpcm(block_size)), AudioCodec::Aac) => format!("caps=audio/x-adpcm,layout=dvi,block_align={},channels=1,rate=8000 ! queue silent=true max-size-bytes=10485760 min-threshold-bytes=1024 ! adpcmdec ! audioconvert ! audioresample ! avenc_aac ! aacparse ! rtpmp4gpay name={}", block_size, pay_name),
+            (Some(StreamFormat::Aac), AudioCodec::Pcm) => format!("! queue silent=true max-size-bytes=10485760 min-threshold-bytes=1024 ! aacparse ! decodebin ! audioconvert ! rtpL16pay name={}", pay_name),
+            (Some(StreamFormat::Aac), AudioCodec::Aac) => format!("! queue silent=true max-size-bytes=10485760 min-threshold-bytes=1024 ! aacparse ! rtpmp4gpay name={}", pay_name),
+            _ => "! fakesink".to_string(),
+        }
     }
 }
 
+// The elements from the video parser to the payloader for the format
+fn launch_pay(
+    format: StreamFormat,
+    transcode_h264: bool,
+    append: Option<&str>,
+    pay_name: &str,
+) -> String {
+    let append = append
+        .map(|elements| format!("! {} ", elements))
+        .unwrap_or_default();
+    match format {
+        StreamFormat::H265 if transcode_h264 => {
+            // This decodes and re-encodes every frame so it is CPU heavy
+            format!("h265parse ! decodebin ! videoconvert ! x264enc tune=zerolatency ! h264parse {}! rtph264pay name={}", append, pay_name)
+        }
+        StreamFormat::H265 => format!("h265parse {}! rtph265pay name={}", append, pay_name),
+        _ => format!("h264parse {}! rtph264pay name={}", append, pay_name),
+    }
+}
+
+// Links the payloader for the typefound caps after the typefind of the dynamic payloader,
+// the rtsp server then adds the stream and finishes preparing the media
+fn link_dynamic_pay(
+    dynpay: &Element,
+    caps: &Caps,
+    transcode_h264: bool,
+    append: Option<&str>,
+) -> std::result::Result<(), glib::BoolError> {
+    let format = match caps.structure(0).map(|s| s.name()) {
+        Some("video/x-h264") => StreamFormat::H264,
+        Some("video/x-h265") => StreamFormat::H265,
+        _ => return Err(glib::bool_error!("Unsupported video format")),
+    };
+    let dynpay = dynpay
+        .clone()
+        .dynamic_cast::<Bin>()
+        .map_err(|_| glib::bool_error!("The dynamic payloader should be a bin"))?;
+    let typefind = dynpay
+        .by_name("vidtypefind")
+        .ok_or_else(|| glib::bool_error!("The typefind should be in the dynamic payloader"))?;
+
+    let pay = gstreamer::parse_bin_from_description(
+        &launch_pay(format, transcode_h264, append, "dynvidpay"),
+        true,
+    )
+    .map_err(|e| glib::bool_error!("{}", e))?;
+    dynpay.add(&pay)?;
+    pay.sync_state_with_parent()?;
+    typefind.link(&pay)?;
+
+    let src = pay
+        .static_pad("src")
+        .ok_or_else(|| glib::bool_error!("The payloader should have a src pad"))?;
+    let ghost = GhostPad::with_target(Some("src"), &src)?;
+    ghost.set_active(true)?;
+    dynpay.add_pad(&ghost)?;
+    dynpay.no_more_pads();
+    Ok(())
+}
+
+// Checks that the extra elements from the config can be built, so that a typo
+// is reported at startup rather than when the first client connects
+pub(crate) fn check_pipeline_elements(elements: &str) -> std::result::Result<(), glib::Error> {
+    gstreamer::parse_bin_from_description(elements, true).map(|_| ())
+}
+
 impl Default for RtspServer {
     fn default() -> RtspServer {
         Self::new()
@@ -165,29 +443,89 @@ impl RtspServer {
         gstreamer::init().expect("Gstreamer should not explode");
         RtspServer {
             server: GstRTSPServer::new(),
+            main_loop: glib::MainLoop::new(None, false),
+            shutdown: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub(crate) fn shutdown_handle(&self) -> ShutdownHandle {
+        ShutdownHandle {
+            main_loop: self.main_loop.clone(),
+            shutdown: self.shutdown.clone(),
         }
     }
 
     pub(crate) fn add_stream(
         &self,
         paths: &[&str],
+        audio_paths: &[&str],
+        keyframe_paths: &[&str],
         permitted_users: &HashSet<&str>,
+        transcode_h264: bool,
+        audio_codec: AudioCodec,
     ) -> Result<GstOutputs> {
-        let mounts = self
-            .server
-            .mount_points()
-            .expect("The server should have mountpoints");
-
         // Create a MaybeAppSrc: Write which we will give the caller.  When the backing AppSrc is
         // created by the factory, fish it out and give it to the waiting MaybeAppSrc via the
         // channel it provided.  This callback may be called more than once by Gstreamer if it is
         // unhappy with the pipeline, so keep updating the MaybeAppSrc.
         let (maybe_app_src, tx) = MaybeAppSrc::new_with_tx();
         let (maybe_app_src_aud, tx_aud) = MaybeAppSrc::new_with_tx();
+        let (maybe_app_src_audonly, tx_audonly) = MaybeAppSrc::new_with_tx();
+        let (maybe_app_src_keyframe, tx_keyframe) = MaybeAppSrc::new_with_tx();
+
+        let outputs = GstOutputs::from_appsrcs(
+            maybe_app_src,
+            maybe_app_src_aud,
+            maybe_app_src_audonly,
+            maybe_app_src_keyframe,
+            transcode_h264,
+            audio_codec,
+            self.shutdown.clone(),
+        );
 
-        let outputs = GstOutputs::from_appsrcs(maybe_app_src, maybe_app_src_aud);
+        self.mount_factory(
+            &outputs.factory,
+            paths,
+            permitted_users,
+            vec![("vidsrc", tx), ("audsrc", tx_aud)],
+            outputs.media_tx.clone(),
+        );
+        if !audio_paths.is_empty() && audio_codec != AudioCodec::None {
+            self.mount_factory(
+                &outputs.audio_factory,
+                audio_paths,
+                permitted_users,
+                vec![("audsrc", tx_audonly)],
+                outputs.media_tx.clone(),
+            );
+        }
+        if !keyframe_paths.is_empty() {
+            self.mount_factory(
+                &outputs.keyframe_factory,
+                keyframe_paths,
+                permitted_users,
+                vec![("vidsrc", tx_keyframe)],
+                outputs.media_tx.clone(),
+            );
+        }
 
-        let factory = &outputs.factory;
+        Ok(outputs)
+    }
+
+    // Mounts the factory on the paths and sends each named appsrc down its
+    // channel whenever the factory creates its media
+    fn mount_factory(
+        &self,
+        factory: &RTSPMediaFactory,
+        paths: &[&str],
+        permitted_users: &HashSet<&str>,
+        app_srcs: Vec<(&'static str, SyncSender<AppSrc>)>,
+        media_tx: SyncSender<()>,
+    ) {
+        let mounts = self
+            .server
+            .mount_points()
+            .expect("The server should have mountpoints");
 
         debug!(
             "Permitting {} to access {}",
@@ -207,26 +545,21 @@ impl RtspServer {
                 .expect("Media should have an element")
                 .dynamic_cast::<Bin>()
                 .expect("Media source's element should be a bin");
-            let app_src = bin
-                .by_name_recurse_up("vidsrc")
-                .expect("write_src must be present in created bin")
-                .dynamic_cast::<AppSrc>()
-                .expect("Source element is expected to be an appsrc!");
-            let _ = tx.send(app_src); // Receiver may be dropped, don't panic if so
-
-            let app_src_aud = bin
-                .by_name_recurse_up("audsrc")
-                .expect("write_src must be present in created bin")
-                .dynamic_cast::<AppSrc>()
-                .expect("Source element is expected to be an appsrc!");
-            let _ = tx_aud.send(app_src_aud); // Receiver may be dropped, don't panic if so
+            for (name, tx) in app_srcs.iter() {
+                let app_src = bin
+                    .by_name_recurse_up(name)
+                    .expect("write_src must be present in created bin")
+                    .dynamic_cast::<AppSrc>()
+                    .expect("Source element is expected to be an appsrc!");
+                let _ = tx.send(app_src); // Receiver may be dropped, don't panic if so
+            }
+            // If this is full the camera is already being woken
+            let _ = media_tx.try_send(());
         });
 
         for path in paths {
             mounts.add_factory(path, factory);
         }
-
-        Ok(outputs)
     }
 
     pub(crate) fn add_permitted_roles(
@@ -302,27 +635,108 @@ impl RtspServer {
         Ok(())
     }
 
+    /// Logs clients as they connect and disconnect, any clients past `max_clients` are
+    /// refused with 503 Service Unavailable
+    pub(crate) fn set_max_clients(&self, max_clients: Option<usize>) {
+        let num_clients = Arc::new(AtomicUsize::new(0));
+        self.server
+            .connect_client_connected(move |_server, client| {
+                let ip = client_ip(client).unwrap_or_else(|| "unknown".to_string());
+                let connected = num_clients.fetch_add(1, Ordering::SeqCst) + 1;
+
+                if let Some(max_clients) = max_clients.filter(|&max| connected > max) {
+                    warn!(
+                        "RTSP: Refusing client {}, the limit of {} clients has been reached",
+                        ip, max_clients
+                    );
+                    client.connect_pre_options_request(|_, _| RTSPStatusCode::ServiceUnavailable);
+                    client.connect_pre_describe_request(|_, _| RTSPStatusCode::ServiceUnavailable);
+                } else {
+                    info!("RTSP: Client {} connected ({} connected)", ip, connected);
+                    let setup_ip = ip.clone();
+                    client.connect_setup_request(move |_client, ctx| {
+                        if let Some(transport) = context_transport(ctx) {
+                            info!(
+                                "RTSP: Client {} is streaming over {:?}",
+                                setup_ip, transport
+                            );
+                        }
+                    });
+                }
+
+                let num_clients = num_clients.clone();
+                client.connect_closed(move |_client| {
+                    let connected = num_clients.fetch_sub(1, Ordering::SeqCst) - 1;
+                    info!("RTSP: Client {} disconnected ({} connected)", ip, connected);
+                });
+            });
+    }
+
+    /// Shuts the server down once the first client disconnects
+    pub(crate) fn stop_after_first_client(&self) {
+        let handle = self.shutdown_handle();
+        let first_client = AtomicBool::new(true);
+        self.server
+            .connect_client_connected(move |_server, client| {
+                // Later clients are refused by the client limit
+                if first_client.swap(false, Ordering::SeqCst) {
+                    let handle = handle.clone();
+                    client.connect_closed(move |_client| {
+                        info!("RTSP: Client finished, shutting down");
+                        handle.shutdown();
+                    });
+                }
+            });
+    }
+
     pub(crate) fn run(&self, bind_addr: &str, bind_port: u16) {
         self.server.set_address(bind_addr);
         self.server.set_service(&format!("{}", bind_port));
         // Attach server to default Glib context
         let _ = self.server.attach(None);
 
-        // Run the Glib main loop.
-        let main_loop = glib::MainLoop::new(None, false);
-        main_loop.run();
+        // Run the Glib main loop until shutdown
+        self.main_loop.run();
+    }
+}
+
+// The bindings do not expose the client's connection so we ask gstreamer directly
+fn client_ip(client: &RTSPClient) -> Option<String> {
+    unsafe {
+        let conn =
+            gstreamer_rtsp_server::ffi::gst_rtsp_client_get_connection(client.to_glib_none().0);
+        if conn.is_null() {
+            return None;
+        }
+        from_glib_none(gstreamer_rtsp::ffi::gst_rtsp_connection_get_ip(conn))
+    }
+}
+
+// The transport that was negotiated during a SETUP request, this is also not exposed by
+// the bindings
+fn context_transport(ctx: &RTSPContext) -> Option<RTSPLowerTrans> {
+    unsafe {
+        let trans = (*ctx.to_glib_none().0).trans;
+        if trans.is_null() {
+            return None;
+        }
+        let transport = gstreamer_rtsp_server::ffi::gst_rtsp_stream_transport_get_transport(trans);
+        if transport.is_null() {
+            return None;
+        }
+        Some(from_glib((*transport).lower_transport))
     }
 }
 
 mod maybe_app_src {
     use super::*;
-    use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
 
     /// A Write implementation around AppSrc that also allows delaying the creation of the AppSrc
     /// until later, discarding written data until the AppSrc is provided.
     pub(crate) struct MaybeAppSrc {
         rx: Receiver<AppSrc>,
         app_src: Option<AppSrc>,
+        waiting_for_keyframe: bool,
     }
 
     impl MaybeAppSrc {
@@ -331,7 +745,14 @@ mod maybe_app_src {
         /// into the AppSrc when write() is called.
         pub(crate) fn new_with_tx() -> (Self, SyncSender<AppSrc>) {
             let (tx, rx) = sync_channel(3); // The sender should not send very often
-            (MaybeAppSrc { rx, app_src: None }, tx)
+            (
+                MaybeAppSrc {
+                    rx,
+                    app_src: None,
+                    waiting_for_keyframe: true,
+                },
+                tx,
+            )
         }
 
         /// Flushes data to Gstreamer on a problem communicating with the underlying video source.
@@ -349,9 +770,31 @@ mod maybe_app_src {
         fn try_get_src(&mut self) -> Option<&AppSrc> {
             while let Some(src) = self.rx.try_recv().ok() {
                 self.app_src = Some(src);
+                self.waiting_for_keyframe = true;
             }
             self.app_src.as_ref()
         }
+
+        /// True if there is an AppSrc to write into, the AppSrc is dropped when the clients of
+        /// its media have all gone
+        pub(crate) fn has_src(&mut self) -> bool {
+            self.try_get_src().is_some()
+        }
+
+        /// Writes a video frame. After a new AppSrc is received frames are discarded until the
+        /// next keyframe, otherwise the first client would start on P-frames with nothing to
+        /// decode them against.
+        pub(crate) fn write_frame(&mut self, buf: &[u8], keyframe: bool) -> io::Result<()> {
+            // Pick up any new AppSrc first so that we know whether to wait
+            self.try_get_src();
+            if keyframe {
+                self.waiting_for_keyframe = false;
+            }
+            if self.waiting_for_keyframe {
+                return Ok(());
+            }
+            self.write_all(buf)
+        }
     }
 
     impl Write for MaybeAppSrc {
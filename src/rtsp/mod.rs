@@ -16,22 +16,36 @@
 ///
 /// `rtsp://my.ip.address:8554/Garage`
 ///
+/// This is the main stream, or the sub or extern stream when the
+/// main stream is not enabled with the `stream` config option.
+///
 /// With the lower resolution stream at
 ///
 /// `rtsp://my.ip.address:8554/Garage/subStream`
 ///
+/// The audio alone is also available at
+///
+/// `rtsp://my.ip.address:8554/Garage/audio`
+///
+/// A low bandwidth stream of only the key frames is at
+///
+/// `rtsp://my.ip.address:8554/Garage/keyframe`
+///
 /// # Usage
 ///
 /// To start the subcommand use the following in a shell.
 ///
 /// ```bash
 /// neolink rtsp --config=config.toml
+/// # To check that a camera works, serve it to a single client then exit
+/// neolink rtsp --config=config.toml --once CameraName
 /// ```
 ///
 use anyhow::{Context, Result};
 use log::*;
 use neolink_core::bc_protocol::{BcCamera, Stream};
 use std::collections::HashSet;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -41,28 +55,64 @@ mod cmdline;
 /// The errors this subcommand can raise
 mod gst;
 
-use super::config::{CameraConfig, Config, UserConfig};
-use crate::utils::AddressOrUid;
+use super::config::{CameraConfig, Config, ProfileConfig, UserConfig};
+use crate::utils::{
+    apply_profile, find_camera_by_name, find_profile_by_name, rediscover_addr, AddressOrUid,
+};
 pub(crate) use cmdline::Opt;
-use gst::{GstOutputs, RtspServer, TlsAuthenticationMode};
+use gst::{
+    check_pipeline_elements, AudioCodec, GstOutputs, RtspServer, ShutdownHandle,
+    TlsAuthenticationMode,
+};
 
 /// Entry point for the rtsp subcommand
 ///
 /// Opt is the command line options
-pub(crate) fn main(_opt: Opt, config: Config) -> Result<()> {
+pub(crate) fn main(opt: Opt, mut config: Config) -> Result<()> {
+    if let Some(name) = &opt.once {
+        // Serve a single stream of the one camera to a single client
+        let mut camera = find_camera_by_name(&config, name)?.clone();
+        if !["mainStream", "subStream", "externStream"]
+            .iter()
+            .any(|&e| e == camera.stream)
+        {
+            camera.stream = "mainStream".to_string();
+        }
+        config.cameras = vec![camera];
+        config.max_rtsp_clients = Some(1);
+    }
+
     let rtsp = &RtspServer::new();
 
     set_up_tls(&config, rtsp);
 
     set_up_users(&config.users, rtsp);
 
+    check_pipelines(&config.cameras)?;
+
+    rtsp.set_max_clients(config.max_rtsp_clients);
+
+    set_up_shutdown(rtsp.shutdown_handle())?;
+
+    // In once mode the first camera failure also stops the server
+    let once = if opt.once.is_some() {
+        rtsp.stop_after_first_client();
+        Some(rtsp.shutdown_handle())
+    } else {
+        None
+    };
+
     if config.certificate == None && !config.users.is_empty() {
         warn!(
             "Without a server certificate, usernames and passwords will be exchanged in plaintext!"
         )
     }
 
-    crossbeam::scope(|s| {
+    // The config is moved into the scope, but the camera threads need the profiles
+    let profiles = config.profiles.clone();
+
+    let camera_results = crossbeam::scope(|s| {
+        let mut camera_threads = vec![];
         for camera in config.cameras {
             if camera.format.is_some() {
                 warn!("The format config option of the camera has been removed in favour of auto detection.")
@@ -75,68 +125,234 @@ pub(crate) fn main(_opt: Opt, config: Config) -> Result<()> {
             let permitted_users =
                 get_permitted_users(config.users.as_slice(), &arc_cam.permitted_users);
 
+            // Checked during config validation
+            let profile = arc_cam
+                .profile
+                .as_ref()
+                .map(|name| find_profile_by_name(&profiles, name).unwrap());
+
+            let transcode_h264 = arc_cam.transcode.as_deref() == Some("h264");
+            let audio_codec = match arc_cam.audio.as_str() {
+                "aac" => AudioCodec::Aac,
+                "none" => AudioCodec::None,
+                _ => AudioCodec::Pcm,
+            };
+
             // Set up each main and substream according to all the RTSP mount paths we support
-            if ["all", "both", "mainStream"].iter().any(|&e| e == arc_cam.stream) {
-                let paths = &[
-                    &*format!("/{}", arc_cam.name),
-                    &*format!("/{}/mainStream", arc_cam.name),
-                ];
-                let mut outputs = rtsp
-                    .add_stream(paths, &permitted_users)
-                    .unwrap();
-                let main_camera = arc_cam.clone();
-                s.spawn(move |_| camera_loop(&*main_camera, Stream::Main, &mut outputs, true));
-            }
-            if ["all", "both", "subStream"].iter().any(|&e| e == arc_cam.stream) {
-                let paths = &[&*format!("/{}/subStream", arc_cam.name)];
-                let mut outputs = rtsp
-                    .add_stream(paths, &permitted_users)
-                    .unwrap();
-                let sub_camera = arc_cam.clone();
-                let manage = arc_cam.stream == "subStream";
-                s.spawn(move |_| camera_loop(&*sub_camera, Stream::Sub, &mut outputs, manage));
-            }
-            if ["all", "externStream"].iter().any(|&e| e == arc_cam.stream) {
-                let paths = &[&*format!("/{}/externStream", arc_cam.name)];
+            for mounts in plan_mounts(&arc_cam.name, &arc_cam.stream) {
+                let paths: Vec<_> = mounts.paths.iter().map(String::as_str).collect();
+                let audio_paths: Vec<_> = mounts.audio_paths.iter().map(String::as_str).collect();
+                let keyframe_paths: Vec<_> =
+                    mounts.keyframe_paths.iter().map(String::as_str).collect();
                 let mut outputs = rtsp
-                    .add_stream(paths, &permitted_users)
+                    .add_stream(
+                        &paths,
+                        &audio_paths,
+                        &keyframe_paths,
+                        &permitted_users,
+                        transcode_h264,
+                        audio_codec,
+                    )
                     .unwrap();
-                let sub_camera = arc_cam.clone();
-                let manage = arc_cam.stream == "externStream";
-                s.spawn(move |_| camera_loop(&*sub_camera, Stream::Extern, &mut outputs, manage));
+                // The main stream manages the camera settings unless it is not served at all,
+                // then the only enabled stream does
+                let manage = match mounts.stream {
+                    Stream::Main => true,
+                    Stream::Sub => arc_cam.stream == "subStream",
+                    Stream::Extern => arc_cam.stream == "externStream",
+                };
+                let stream_camera = arc_cam.clone();
+                let once = once.clone();
+                camera_threads.push(s.spawn(move |_| {
+                    camera_loop(
+                        &*stream_camera,
+                        profile,
+                        mounts.stream,
+                        &mut outputs,
+                        manage,
+                        once,
+                    )
+                }));
             }
         }
 
         rtsp.run(&config.bind_addr, config.bind_port);
+        info!("Waiting for the cameras to log out");
+        camera_threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .collect::<Vec<_>>()
     })
     .unwrap();
 
+    if opt.once.is_some() {
+        // Report the failure through the exit code
+        for result in camera_results {
+            result?;
+        }
+    }
+
     Ok(())
 }
 
+/// The rtsp paths served by one stream of a camera
+#[derive(Debug, PartialEq)]
+struct StreamMounts {
+    stream: Stream,
+    paths: Vec<String>,
+    audio_paths: Vec<String>,
+    keyframe_paths: Vec<String>,
+}
+
+/// Work out which streams of the camera `name` to serve, and on which paths, from its
+/// `stream` config option
+///
+/// The base path and the audio only and keyframe only paths are served by whichever
+/// stream is set up first, so a camera with only the sub or extern stream enabled is
+/// still at the base path
+fn plan_mounts(name: &str, stream: &str) -> Vec<StreamMounts> {
+    let streams = [
+        (
+            Stream::Main,
+            "mainStream",
+            &["all", "both", "mainStream"][..],
+        ),
+        (Stream::Sub, "subStream", &["all", "both", "subStream"][..]),
+        (Stream::Extern, "externStream", &["all", "externStream"][..]),
+    ];
+
+    let mut first_paths = Some((
+        vec![format!("/{}", name)],
+        vec![format!("/{}/audio", name)],
+        vec![format!("/{}/keyframe", name)],
+    ));
+
+    streams
+        .iter()
+        .filter(|(_, _, enabled_by)| enabled_by.contains(&stream))
+        .map(|&(stream, stream_name, _)| {
+            let (mut paths, audio_paths, keyframe_paths) = first_paths.take().unwrap_or_default();
+            paths.push(format!("/{}/{}", name, stream_name));
+            StreamMounts {
+                stream,
+                paths,
+                audio_paths,
+                keyframe_paths,
+            }
+        })
+        .collect()
+}
+
+// On SIGINT/SIGTERM stop the server and let each camera stream end so that it
+// is logged out, otherwise the camera can count us as connected until it times out
+fn set_up_shutdown(handle: ShutdownHandle) -> Result<()> {
+    ctrlc::set_handler(move || {
+        if handle.is_shutdown() {
+            warn!("Shutdown requested again, exiting without logging out");
+            std::process::exit(1);
+        }
+        info!("Shutting down, press Ctrl-C again to force");
+        handle.shutdown();
+    })
+    .context("Unable to set the shutdown signal handler")
+}
+
 fn camera_loop(
     camera_config: &CameraConfig,
+    profile: Option<&ProfileConfig>,
     stream_name: Stream,
     outputs: &mut GstOutputs,
     manage: bool,
+    once: Option<ShutdownHandle>,
 ) -> Result<(), anyhow::Error> {
     let min_backoff = Duration::from_secs(1);
     let max_backoff = Duration::from_secs(15);
     let mut current_backoff = min_backoff;
+    // Number of failed connections by address before finding the camera with its uid
+    let max_addr_failures = 3;
+    let mut addr_failures = 0;
+    // The address found by the uid when the config address stopped working
+    let mut rediscovered_addr: Option<SocketAddr> = None;
+    outputs.on_demand = camera_config.on_demand_only;
+    outputs.set_latency(camera_config.rtsp_latency_ms);
+    outputs.set_pipeline_elements(
+        camera_config.pipeline_prepend.clone(),
+        camera_config.pipeline_append.clone(),
+    );
 
     loop {
-        if let Err(cam_err) = camera_main(camera_config, stream_name, outputs, manage) {
-            outputs.vidsrc.on_stream_error();
-            outputs.audsrc.on_stream_error();
+        if outputs.is_shutdown() {
+            outputs.end_of_stream();
+            return Ok(());
+        }
+        if outputs.on_demand && !outputs.has_client() {
+            // Don't contact the camera at all until someone wants to watch, we are woken as
+            // soon as a client's media is configured and it waits for the stream to start
+            outputs.wait_for_client(Duration::from_millis(500));
+            continue;
+        }
+        let camera_addr = match rediscovered_addr {
+            Some(addr) => AddressOrUid::Address(addr.to_string()),
+            None => {
+                AddressOrUid::new(&camera_config.camera_addr, &camera_config.camera_uid).unwrap()
+            }
+        };
+        if let Err(cam_err) = camera_main(
+            camera_config,
+            profile,
+            stream_name,
+            outputs,
+            manage,
+            &camera_addr,
+        ) {
+            // The clients are kept attached while we reconnect, the camera restarts the stream
+            // on a key frame so they carry on once it is back. Some clients such as Blue Iris give
+            // up for good if their stream ends, so only end it when we stop retrying
+            //
             // Authentication failures are permanent; we retry everything else
             if cam_err.connected {
                 current_backoff = min_backoff;
+                addr_failures = 0;
+                // The session worked, so the config address is tried again first next time
+                rediscovered_addr = None;
+            } else {
+                addr_failures += 1;
+            }
+            if camera_config.rediscover_on_failure
+                && camera_config.camera_addr.is_some()
+                && camera_config.camera_uid.is_some()
+                && addr_failures >= max_addr_failures
+            {
+                // The address is likely stale (e.g. a new DHCP lease), find the camera with its
+                // uid and connect to the new address as normal until a session succeeds
+                warn!(
+                    "{}: Failed to connect by address {} times, rediscovering by UID",
+                    camera_config.name, addr_failures
+                );
+                addr_failures = 0;
+                match rediscover_addr(camera_config) {
+                    Ok(addr) => {
+                        info!("{}: Rediscovered at {}", camera_config.name, addr);
+                        rediscovered_addr = Some(addr);
+                    }
+                    Err(e) => warn!("{}: {:?}", camera_config.name, e),
+                }
+            }
+            if let Some(handle) = once {
+                error!(
+                    "Error streaming from camera {}, not retrying: {:?}",
+                    camera_config.name, cam_err.err
+                );
+                outputs.end_of_stream();
+                handle.shutdown();
+                return Err(cam_err.err);
             }
             if cam_err.login_fail {
                 error!(
-                    "Authentication failed to camera {}, not retrying",
+                    "Authentication failed to camera {}, check the username and password, not retrying",
                     camera_config.name
                 );
+                outputs.end_of_stream();
                 return Err(cam_err.err);
             } else {
                 error!(
@@ -149,9 +365,17 @@ fn camera_loop(
 
             std::thread::sleep(current_backoff);
             current_backoff = std::cmp::min(max_backoff, current_backoff * 2);
+        } else if outputs.on_demand && !outputs.is_shutdown() {
+            addr_failures = 0;
+            rediscovered_addr = None;
+            info!(
+                "{}: No clients left, waiting for the next one",
+                camera_config.name
+            );
         } else {
-            // Should not occur because we don't set the callback up
-            // in such a way that it requests graceful shutdown
+            // Otherwise the stream only ends without error when we are shutting down
+            outputs.end_of_stream();
+            info!("{}: Stream stopped", camera_config.name);
             return Ok(());
         }
     }
@@ -176,6 +400,21 @@ fn set_up_tls(config: &Config, rtsp: &RtspServer) {
     }
 }
 
+fn check_pipelines(cameras: &[CameraConfig]) -> Result<()> {
+    for camera in cameras {
+        let extra_elements = [&camera.pipeline_prepend, &camera.pipeline_append];
+        for elements in extra_elements.iter().copied().flatten() {
+            check_pipeline_elements(elements).with_context(|| {
+                format!(
+                    "{}: Invalid gstreamer pipeline elements {:?}",
+                    camera.name, elements
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
 fn set_up_users(users: &[UserConfig], rtsp: &RtspServer) {
     // Setting up users
     let credentials: Vec<_> = users
@@ -210,17 +449,17 @@ fn get_permitted_users<'a>(
 
 fn camera_main(
     camera_config: &CameraConfig,
+    profile: Option<&ProfileConfig>,
     stream_name: Stream,
     outputs: &mut GstOutputs,
     manage: bool,
+    camera_addr: &AddressOrUid,
 ) -> Result<(), CameraErr> {
     let mut connected = false;
     let mut login_fail = false;
     (|| {
-        let camera_addr =
-            AddressOrUid::new(&camera_config.camera_addr, &camera_config.camera_uid).unwrap();
         let mut camera =
-            camera_addr.connect_camera(camera_config.channel_id)
+            camera_addr.connect_camera(camera_config)
                 .with_context(|| {
                     format!(
                         "Failed to connect to camera {} at {} on channel {}",
@@ -252,7 +491,7 @@ fn camera_main(
         info!("{}: Connected and logged in", camera_config.name);
 
         if manage {
-            do_camera_management(&mut camera, camera_config).context("Failed to manage the camera settings")?;
+            do_camera_management(&mut camera, camera_config, profile).context("Failed to manage the camera settings")?;
         }
 
         let stream_display_name = match stream_name {
@@ -273,7 +512,11 @@ fn camera_main(
     })
 }
 
-fn do_camera_management(camera: &mut BcCamera, camera_config: &CameraConfig) -> Result<()> {
+fn do_camera_management(
+    camera: &mut BcCamera,
+    camera_config: &CameraConfig,
+    profile: Option<&ProfileConfig>,
+) -> Result<()> {
     let cam_time = camera.get_time()?;
     if let Some(time) = cam_time {
         info!(
@@ -320,5 +563,110 @@ fn do_camera_management(camera: &mut BcCamera, camera_config: &CameraConfig) ->
         );
     }
 
+    // Re-asserted on every connection as the camera may have reset its settings on reboot
+    if let Some(profile) = profile {
+        if apply_profile(camera, profile)? {
+            info!("{}: Applied profile {}", camera_config.name, profile.name);
+        } else {
+            debug!(
+                "{}: Camera already matches profile {}",
+                camera_config.name, profile.name
+            );
+        }
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mounts(stream: Stream, paths: &[&str], audio: &[&str], keyframe: &[&str]) -> StreamMounts {
+        let to_strings = |p: &[&str]| p.iter().map(|s| s.to_string()).collect();
+        StreamMounts {
+            stream,
+            paths: to_strings(paths),
+            audio_paths: to_strings(audio),
+            keyframe_paths: to_strings(keyframe),
+        }
+    }
+
+    #[test]
+    fn test_plan_mounts_main() {
+        assert_eq!(
+            plan_mounts("Cam", "mainStream"),
+            vec![mounts(
+                Stream::Main,
+                &["/Cam", "/Cam/mainStream"],
+                &["/Cam/audio"],
+                &["/Cam/keyframe"]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_plan_mounts_sub() {
+        // Without the main stream the sub stream takes over the base path
+        assert_eq!(
+            plan_mounts("Cam", "subStream"),
+            vec![mounts(
+                Stream::Sub,
+                &["/Cam", "/Cam/subStream"],
+                &["/Cam/audio"],
+                &["/Cam/keyframe"]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_plan_mounts_extern() {
+        assert_eq!(
+            plan_mounts("Cam", "externStream"),
+            vec![mounts(
+                Stream::Extern,
+                &["/Cam", "/Cam/externStream"],
+                &["/Cam/audio"],
+                &["/Cam/keyframe"]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_plan_mounts_both() {
+        assert_eq!(
+            plan_mounts("Cam", "both"),
+            vec![
+                mounts(
+                    Stream::Main,
+                    &["/Cam", "/Cam/mainStream"],
+                    &["/Cam/audio"],
+                    &["/Cam/keyframe"]
+                ),
+                mounts(Stream::Sub, &["/Cam/subStream"], &[], &[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_mounts_all() {
+        assert_eq!(
+            plan_mounts("Cam", "all"),
+            vec![
+                mounts(
+                    Stream::Main,
+                    &["/Cam", "/Cam/mainStream"],
+                    &["/Cam/audio"],
+                    &["/Cam/keyframe"]
+                ),
+                mounts(Stream::Sub, &["/Cam/subStream"], &[], &[]),
+                mounts(Stream::Extern, &["/Cam/externStream"], &[], &[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_mounts_none() {
+        assert_eq!(plan_mounts("Cam", "none"), vec![]);
+    }
+}
@@ -0,0 +1,10 @@
+use structopt::StructOpt;
+
+/// The set-name command will change the name the camera reports in the Reolink app
+#[derive(StructOpt, Debug)]
+pub struct Opt {
+    /// The name of the camera. Must be a name in the config
+    pub camera: String,
+    /// The new device name for the camera
+    pub name: String,
+}
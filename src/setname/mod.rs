@@ -0,0 +1,40 @@
+///
+/// # Neolink Set Name
+///
+/// This module handles the name the camera reports for itself.
+/// This is the name shown in the Reolink app, it is not the
+/// name used for the camera in the neolink config
+///
+/// # Usage
+///
+/// ```bash
+/// neolink set-name --config=config.toml CameraName "Front Door"
+/// ```
+///
+use anyhow::{Context, Result};
+use log::*;
+
+mod cmdline;
+
+use super::config::Config;
+use crate::utils::find_and_connect;
+pub(crate) use cmdline::Opt;
+
+/// Entry point for the set-name subcommand
+///
+/// Opt is the command line options
+pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
+    let camera = find_and_connect(&config, &opt.camera)?;
+
+    let old_name = camera
+        .get_device_name()
+        .context("Unable to get camera device name")?;
+    camera
+        .set_device_name(&opt.name)
+        .context("Unable to set camera device name")?;
+    info!(
+        "{}: Device name changed from {:?} to {:?}",
+        opt.camera, old_name, opt.name
+    );
+    Ok(())
+}
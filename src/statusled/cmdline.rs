@@ -17,7 +17,7 @@ fn onoff_parse(src: &str) -> Result<bool> {
 pub struct Opt {
     /// The name of the camera to change the lights of. Must be a name in the config
     pub camera: String,
-    /// Whether to turn the light on or off
+    /// Whether to turn the light on or off. If not given the current state is printed
     #[structopt(parse(try_from_str = onoff_parse), name = "on|off")]
-    pub on: bool,
+    pub on: Option<bool>,
 }
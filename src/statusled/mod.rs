@@ -13,9 +13,12 @@
 /// neolink status-light --config=config.toml CameraName on
 /// # Or off
 /// neolink status-light --config=config.toml CameraName off
+/// # To print whether the light is on
+/// neolink status-light --config=config.toml CameraName
 /// ```
 ///
 use anyhow::{Context, Result};
+use log::*;
 
 mod cmdline;
 
@@ -29,8 +32,20 @@ pub(crate) use cmdline::Opt;
 pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
     let mut camera = find_and_connect(&config, &opt.camera)?;
 
-    camera
-        .led_light_set(opt.on)
-        .context("Unable to set camera light state")?;
+    if let Some(on) = opt.on {
+        camera
+            .led_light_set(on)
+            .context("Unable to set camera light state")?;
+    } else {
+        let led_state = camera
+            .get_ledstate()
+            .context("Unable to get camera light state")?;
+        let state = match led_state.light_state.as_str() {
+            "open" => "on",
+            "close" => "off",
+            other => other,
+        };
+        info!("{}: Status light is {}", opt.camera, state);
+    }
     Ok(())
 }
@@ -11,8 +11,15 @@ use structopt::StructOpt;
 pub struct Opt {
     /// The name of the camera to talk through. Must be a name in the config
     pub camera: String,
-    /// The path to the audio file.
-    #[structopt(short, long, parse(from_os_str), conflicts_with = "microphone")]
+    /// The path to the audio file. Any format gstreamer can decode such as wav or mp3,
+    /// it is converted to the format the camera needs
+    #[structopt(
+        short,
+        long,
+        alias = "file",
+        parse(from_os_str),
+        conflicts_with = "microphone"
+    )]
     pub file_path: Option<PathBuf>,
     /// Use the microphone as the source. Defaults to autoaudiosrc - Which microphone depends
     /// on [gstreamer](https://gstreamer.freedesktop.org/documentation/autodetect/autoaudiosrc.html?gi-language=c#autoaudiosrc-page)
@@ -26,7 +33,17 @@ pub struct Opt {
         conflicts_with = "file_path"
     )]
     pub input_src: String,
+    /// The audioStreamMode to talk with, such as followVideoStream or talkVideoStream.
+    /// Must be one of the modes the camera supports. Defaults to followVideoStream when supported
+    #[structopt(long)]
+    pub stream_mode: Option<String>,
     /// Use to change the volume of the input
     #[structopt(short, long, default_value = "1.0")]
     pub volume: f32,
+    /// Number of times to retry the talk if it fails
+    #[structopt(long, default_value = "0")]
+    pub retries: u32,
+    /// Stop retrying after this many seconds
+    #[structopt(long)]
+    pub timeout: Option<u64>,
 }
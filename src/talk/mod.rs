@@ -1,31 +1,87 @@
 ///
 /// # Neolink Talk
 ///
-/// This module can be used to send adpcm data for the camera to play
+/// This module can be used to send audio for the camera to play
 ///
-/// The adpcm data needs to be in DVI-4 layout
+/// Any audio file that gstreamer can decode, such as wav or mp3, is
+/// converted to the adpcm sample rate and block size that the camera
+/// reports in its TalkAbility
 ///
 /// # Usage
 ///
 /// ```bash
-/// neolink talk --config=config.toml --adpcm-file=data.adpcm --sample-rate=16000 --block-size=512 CameraName
+/// neolink talk --config=config.toml --file=announcement.wav CameraName
+/// # Retry up to two more times if the talk fails, giving up after 30s
+/// neolink talk --config=config.toml --file-path=data.wav --retries=2 --timeout=30 CameraName
 /// ```
 ///
 use anyhow::{anyhow, Context, Result};
+use log::*;
 use neolink_core::bc::xml::TalkConfig;
+use std::time::{Duration, Instant};
 
 mod cmdline;
 mod gst;
 
-use super::config::Config;
+use super::config::{CameraConfig, Config};
 use crate::utils::{connect_and_login, find_camera_by_name};
 pub(crate) use cmdline::Opt;
 
+/// How long to wait before retrying a talk the camera was too busy for
+const BUSY_RETRY_DELAY: Duration = Duration::from_secs(2);
+
 /// Entry point for the talk subcommand
 ///
 /// Opt is the command line options
 pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
     let camera_config = find_camera_by_name(&config, &opt.camera)?;
+    let deadline = opt
+        .timeout
+        .map(|timeout| Instant::now() + Duration::from_secs(timeout));
+
+    let mut attempt = 0;
+    loop {
+        let result = talk(&opt, camera_config);
+        let timed_out = matches!(deadline, Some(deadline) if Instant::now() >= deadline);
+        match result {
+            Ok(()) => return Ok(()),
+            // Retrying will not change the camera's mind about the audio, but a
+            // busy camera is worth retrying once the other talk has finished
+            Err(e) if is_talk_rejected(&e) => return Err(e),
+            Err(e) if attempt < opt.retries && !timed_out => {
+                attempt += 1;
+                warn!(
+                    "{}: Talk failed, retrying ({}/{}): {:?}",
+                    camera_config.name, attempt, opt.retries, e
+                );
+                if is_talk_busy(&e) {
+                    // Give the other talk a chance to finish
+                    std::thread::sleep(BUSY_RETRY_DELAY);
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn is_talk_busy(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<neolink_core::Error>(),
+        Some(neolink_core::Error::TalkRejected(rejection)) if rejection.is_busy()
+    )
+}
+
+// Whether the camera refused the talk for a reason that retrying will not fix
+fn is_talk_rejected(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<neolink_core::Error>(),
+        Some(neolink_core::Error::TalkRejected(rejection)) if !rejection.is_busy()
+    )
+}
+
+// Connects to the camera and plays the input through it. Everything is set up
+// again on each call so that it can be retried after a dropped connection
+fn talk(opt: &Opt, camera_config: &CameraConfig) -> Result<()> {
     let camera = connect_and_login(camera_config)?;
 
     let talk_ability = camera
@@ -45,12 +101,37 @@ pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
     // than one ability
     let config = 0;
 
+    let stream_modes: Vec<&str> = talk_ability
+        .audio_stream_mode_list
+        .iter()
+        .map(|mode| mode.audio_stream_mode.as_str())
+        .collect();
+    let audio_stream_mode = match &opt.stream_mode {
+        Some(mode) if stream_modes.contains(&mode.as_str()) => mode.clone(),
+        Some(mode) => {
+            return Err(anyhow!(
+                "Camera {} does not support the talk stream mode {}, it supports: {}",
+                camera_config.name,
+                mode,
+                stream_modes.join(", ")
+            ))
+        }
+        // followVideoStream is the mode seen working on most cameras
+        None => stream_modes
+            .iter()
+            .find(|&&mode| mode == "followVideoStream")
+            .unwrap_or(&stream_modes[config])
+            .to_string(),
+    };
+    debug!(
+        "{}: Talking with stream mode {}",
+        camera_config.name, audio_stream_mode
+    );
+
     let talk_config = TalkConfig {
         channel_id: camera_config.channel_id,
         duplex: talk_ability.duplex_list[config].duplex.clone(),
-        audio_stream_mode: talk_ability.audio_stream_mode_list[config]
-            .audio_stream_mode
-            .clone(),
+        audio_stream_mode: audio_stream_mode.clone(),
         audio_config: talk_ability.audio_config_list[config].audio_config.clone(),
         ..Default::default()
     };
@@ -67,8 +148,12 @@ pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
     let rx = match (&opt.file_path, &opt.microphone) {
         (Some(path), false) => gst::from_input(
             &format!(
-                "filesrc location={}",
-                path.to_str().expect("File path not UTF8 complient")
+                "filesrc location=\"{}\"",
+                // Quoted so that paths with spaces survive gst_parse_launch
+                path.to_str()
+                    .expect("File path not UTF8 complient")
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
             ),
             opt.volume,
             block_size,
@@ -80,9 +165,14 @@ pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
         _ => unreachable!(),
     };
 
-    camera
-        .talk_stream(rx, talk_config)
-        .context("Talk stream ended early")?;
+    camera.talk_stream(rx, talk_config).with_context(|| {
+        format!(
+            "Talk stream ended early using stream mode {}, the camera supports: {}. \
+                Another one can be tried with --stream-mode",
+            audio_stream_mode,
+            stream_modes.join(", ")
+        )
+    })?;
 
     Ok(())
 }
@@ -0,0 +1,5 @@
+use structopt::StructOpt;
+
+/// The test-config command will check the config file and try to login to every camera in it
+#[derive(StructOpt, Debug)]
+pub struct Opt {}
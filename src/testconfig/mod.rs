@@ -0,0 +1,66 @@
+///
+/// # Neolink Test Config
+///
+/// This module handles the test-config subcommand
+///
+/// The subcommand validates the config file and then tries to connect
+/// and login to each camera in it. The result for every camera is
+/// printed at the end so that a wrong password or uid can be spotted
+/// before starting the rtsp server.
+///
+/// # Usage
+///
+/// ```bash
+/// neolink test-config --config=config.toml
+/// ```
+///
+use anyhow::{anyhow, Result};
+
+mod cmdline;
+
+use super::config::Config;
+use crate::utils::connect_and_login;
+pub(crate) use cmdline::Opt;
+
+/// Entry point for the test-config subcommand
+///
+/// Opt is the command line options
+pub(crate) fn main(_opt: Opt, config: Config) -> Result<()> {
+    // The config was already validated before the subcommand is run
+    let results: Vec<_> = config
+        .cameras
+        .iter()
+        .map(|camera_config| {
+            let result = connect_and_login(camera_config).map(|_| ());
+            (&camera_config.name, result)
+        })
+        .collect();
+
+    let width = results
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        .max("Camera".len());
+    println!("{:width$}  Result", "Camera", width = width);
+    let mut failures = 0;
+    for (name, result) in results.iter() {
+        match result {
+            Ok(()) => println!("{:width$}  PASS", name, width = width),
+            Err(e) => {
+                failures += 1;
+                println!("{:width$}  FAIL: {:#}", name, e, width = width);
+            }
+        }
+    }
+
+    if failures > 0 {
+        Err(anyhow!(
+            "{} of {} cameras failed to connect",
+            failures,
+            results.len()
+        ))
+    } else {
+        Ok(())
+    }
+}
@@ -2,10 +2,14 @@
 //!
 use log::*;
 
-use super::config::{CameraConfig, Config};
+use super::config::{CameraConfig, Config, ProfileConfig, StreamProfileConfig};
 use anyhow::{anyhow, Context, Error, Result};
-use neolink_core::bc_protocol::BcCamera;
+use neolink_core::{
+    bc::xml::{StreamCompression, TimeBlock, TimeBlockList},
+    bc_protocol::{BcCamera, DiscoveryMethod, UdpOptions},
+};
 use std::fmt::{Display, Error as FmtError, Formatter};
+use std::net::SocketAddr;
 
 pub(crate) enum AddressOrUid {
     Address(String),
@@ -23,21 +27,39 @@ impl Display for AddressOrUid {
 
 impl AddressOrUid {
     // Created by translating the config fields directly
+    //
+    // If both are given the address is preferred, the uid is then only used
+    // to rediscover the camera when the address stops working
     pub(crate) fn new(address: &Option<String>, uid: &Option<String>) -> Result<Self, Error> {
         match (address, uid) {
             (None, None) => Err(anyhow!("Neither address or uid given")),
-            (Some(_), Some(_)) => Err(anyhow!("Either address or uid should be given not both")),
-            (Some(host), None) => Ok(AddressOrUid::Address(host.clone())),
+            (Some(host), _) => Ok(AddressOrUid::Address(host.clone())),
             (None, Some(host)) => Ok(AddressOrUid::Uid(host.clone())),
         }
     }
 
     // Convience method to get the BcCamera with the appropiate method
-    pub(crate) fn connect_camera(&self, channel_id: u8) -> Result<BcCamera, Error> {
-        match self {
-            AddressOrUid::Address(host) => Ok(BcCamera::new_with_addr(host, channel_id)?),
-            AddressOrUid::Uid(host) => Ok(BcCamera::new_with_uid(host, channel_id)?),
+    //
+    // Also starts the packet dump if one was requested
+    pub(crate) fn connect_camera(&self, camera_config: &CameraConfig) -> Result<BcCamera, Error> {
+        let channel_id = camera_config.channel_id;
+        let camera = match self {
+            AddressOrUid::Address(host) => BcCamera::new_with_addr(host, channel_id)?,
+            AddressOrUid::Uid(host) => {
+                let options = udp_options(camera_config);
+                if let Some(tcp_port) = camera_config.uid_tcp_port {
+                    BcCamera::new_with_uid_over_tcp(host, channel_id, &options, tcp_port)?
+                } else {
+                    BcCamera::new_with_uid_and_discovery(host, channel_id, &options)?
+                }
+            }
+        };
+        if let Some(dump_dir) = &camera_config.dump_packets {
+            camera
+                .dump_packets(dump_dir, &camera_config.name)
+                .with_context(|| format!("Failed to start the packet dump in {:?}", dump_dir))?;
         }
+        Ok(camera)
     }
 }
 
@@ -54,14 +76,12 @@ pub(crate) fn connect_and_login(camera_config: &CameraConfig) -> Result<BcCamera
         camera_config.name, camera_addr
     );
 
-    let mut camera = camera_addr
-        .connect_camera(camera_config.channel_id)
-        .with_context(|| {
-            format!(
-                "Failed to connect to camera {} at {} on channel {}",
-                camera_config.name, camera_addr, camera_config.channel_id
-            )
-        })?;
+    let mut camera = camera_addr.connect_camera(camera_config).with_context(|| {
+        format!(
+            "Failed to connect to camera {} at {} on channel {}",
+            camera_config.name, camera_addr, camera_config.channel_id
+        )
+    })?;
 
     info!("{}: Logging in", camera_config.name);
     camera
@@ -83,3 +103,167 @@ pub(crate) fn find_camera_by_name<'a, 'b>(
         .find(|c| c.name == name)
         .ok_or_else(|| anyhow!("Camera {} not found in the config file", name))
 }
+
+pub(crate) fn find_profile_by_name<'a>(
+    profiles: &'a [ProfileConfig],
+    name: &str,
+) -> Result<&'a ProfileConfig> {
+    profiles
+        .iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| anyhow!("Profile {} not found in the config file", name))
+}
+
+// Writes the profile to the camera if the camera's current settings differ from it
+//
+// Returns true if the camera's settings were changed
+pub(crate) fn apply_profile(camera: &BcCamera, profile: &ProfileConfig) -> Result<bool> {
+    let mut compression = camera
+        .get_compression()
+        .context("Unable to get camera compression")?;
+
+    let mut changed = false;
+    if let Some(stream_profile) = &profile.main_stream {
+        changed |= apply_stream_profile(&mut compression.main_stream, stream_profile);
+    }
+    if let Some(stream_profile) = &profile.sub_stream {
+        changed |= apply_stream_profile(&mut compression.sub_stream, stream_profile);
+    }
+
+    if changed {
+        // These are only sent by the camera
+        compression.is_no_translate_frame = None;
+        compression.third_stream = None;
+        camera
+            .set_compression(compression)
+            .context("Unable to set camera compression")?;
+    }
+    Ok(changed)
+}
+
+fn apply_stream_profile(stream: &mut StreamCompression, profile: &StreamProfileConfig) -> bool {
+    let before = (stream.width, stream.height, stream.bit_rate, stream.frame);
+    if let (Some(width), Some(height)) = (profile.width, profile.height) {
+        stream.width = width;
+        stream.height = height;
+        stream.resolution_name = format!("{}*{}", width, height);
+    }
+    if let Some(bitrate) = profile.bitrate {
+        stream.bit_rate = bitrate;
+    }
+    if let Some(fps) = profile.fps {
+        stream.frame = fps;
+    }
+    before != (stream.width, stream.height, stream.bit_rate, stream.frame)
+}
+
+// Finds the current address of the camera with its uid, for when the address in the
+// config has gone stale
+//
+// The returned address has the ip the camera was found at and the port of the
+// config address, so it can be connected to over TCP like the config address
+pub(crate) fn rediscover_addr(camera_config: &CameraConfig) -> Result<SocketAddr> {
+    let uid = camera_config
+        .camera_uid
+        .as_ref()
+        .ok_or_else(|| anyhow!("Cannot rediscover {} without a uid", camera_config.name))?;
+    let udp_addr = BcCamera::discover_addr(uid, &udp_options(camera_config))
+        .with_context(|| format!("Failed to discover {} by its uid", camera_config.name))?;
+    Ok(SocketAddr::new(udp_addr.ip(), tcp_port(camera_config)))
+}
+
+// The TCP port of the camera from the uid_tcp_port or the address config fields
+fn tcp_port(camera_config: &CameraConfig) -> u16 {
+    camera_config
+        .uid_tcp_port
+        .or_else(|| {
+            camera_config
+                .camera_addr
+                .as_ref()
+                .and_then(|addr| addr.rsplit(':').next()?.parse().ok())
+        })
+        .unwrap_or(9000)
+}
+
+fn udp_options(camera_config: &CameraConfig) -> UdpOptions {
+    UdpOptions {
+        discovery: discovery_order(camera_config),
+        relay_hostnames: camera_config.p2p_relay_servers.clone(),
+        mtu: camera_config.udp_mtu,
+        source_ip: camera_config.source_ip,
+    }
+}
+
+// Translates the discovery_order config strings, these are checked during validation
+pub(crate) fn discovery_order(camera_config: &CameraConfig) -> Vec<DiscoveryMethod> {
+    camera_config
+        .discovery_order
+        .iter()
+        .map(|method| match method.as_str() {
+            "local" => DiscoveryMethod::Local,
+            "remote" => DiscoveryMethod::Remote,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+// Parses the on|off arguments of the subcommands
+pub(crate) fn onoff_parse(src: &str) -> Result<bool> {
+    match src {
+        "true" | "on" | "yes" => Ok(true),
+        "false" | "off" | "no" => Ok(false),
+        _ => Err(anyhow!(
+            "Could not understand {}, check your input, should be true/false, on/off or yes/no",
+            src
+        )),
+    }
+}
+
+// Parses the BEGIN-END hours of the day arguments of the subcommands
+pub(crate) fn hours_parse(src: &str) -> Result<(u8, u8)> {
+    let (begin, end) = src.split_once('-').ok_or_else(|| {
+        anyhow!(
+            "Could not understand {}, should be BEGIN-END e.g. 9-17",
+            src
+        )
+    })?;
+    let begin: u8 = begin
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid begin hour in {}", src))?;
+    let end: u8 = end
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid end hour in {}", src))?;
+    if begin > 23 || end > 23 || begin > end {
+        return Err(anyhow!(
+            "Hours in {} should be between 0 and 23 with BEGIN no later than END",
+            src
+        ));
+    }
+    Ok((begin, end))
+}
+
+// A schedule of the same hours on every day of the week
+pub(crate) fn every_day(begin_hour: u8, end_hour: u8) -> TimeBlockList {
+    let week_days = [
+        "Sunday",
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+    ];
+    TimeBlockList {
+        time_block: week_days
+            .iter()
+            .map(|week_day| TimeBlock {
+                enable: 1,
+                week_day: week_day.to_string(),
+                begin_hour,
+                end_hour,
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,8 @@
+use structopt::StructOpt;
+
+/// The version-check command will print the neolink and camera firmware versions for bug reports
+#[derive(StructOpt, Debug)]
+pub struct Opt {
+    /// The name of the camera. Must be a name in the config
+    pub camera: String,
+}
@@ -0,0 +1,70 @@
+///
+/// # Neolink Version Check
+///
+/// This module handles the version-check subcommand
+///
+/// The subcommand prints the version of neolink and the model and
+/// firmware of the camera in a form that can be pasted into a bug
+/// report.
+///
+/// # Usage
+///
+/// ```bash
+/// neolink version-check --config=config.toml CameraName
+/// ```
+///
+use anyhow::{Context, Result};
+
+mod cmdline;
+
+use super::config::Config;
+use crate::utils::{connect_and_login, find_camera_by_name, AddressOrUid};
+pub(crate) use cmdline::Opt;
+
+/// Entry point for the version-check subcommand
+///
+/// Opt is the command line options
+pub(crate) fn main(opt: Opt, config: Config) -> Result<()> {
+    let camera_config = find_camera_by_name(&config, &opt.camera)?;
+    let camera = connect_and_login(camera_config)?;
+
+    let version = camera
+        .version()
+        .context("Could not get the version info of the camera")?;
+
+    // The address is preferred when both are given, see AddressOrUid::new
+    let (connection, note) =
+        match AddressOrUid::new(&camera_config.camera_addr, &camera_config.camera_uid)? {
+            AddressOrUid::Address(_) => ("TCP (address)", None),
+            AddressOrUid::Uid(_) => (
+                "UDP (uid)",
+                Some(
+                    "UDP support is the newest part of neolink. If the stream has problems \
+                    please also attach a capture made with --dump-packets",
+                ),
+            ),
+        };
+
+    println!("```");
+    println!(
+        "Neolink: {} {}",
+        env!("NEOLINK_VERSION").trim(),
+        env!("NEOLINK_PROFILE")
+    );
+    println!("Neolink core: {}", neolink_core::VERSION);
+    println!("Camera model: {}", version.hardwareVersion);
+    println!("Camera detail: {}", version.detail);
+    println!(
+        "Camera firmware: {} ({})",
+        version.firmwareVersion, version.buildDay
+    );
+    println!("Connection: {}", connection);
+    println!("Channel: {}", camera_config.channel_id);
+    println!("```");
+
+    if let Some(note) = note {
+        println!("{}", note);
+    }
+
+    Ok(())
+}